@@ -0,0 +1,110 @@
+// Compares the git CLI with the in-process (libgit2) backend on the read paths that
+// `GitService` answers in-process. Run with `cargo bench --bench git_read`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use issue_marionette_lib::services::{GitReadBackend, GitReader, GitService};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tokio::runtime::Runtime;
+
+const BACKENDS: [(&str, GitReadBackend); 2] = [
+    ("cli", GitReadBackend::Cli),
+    ("in-process", GitReadBackend::InProcess),
+];
+
+// A repository with a few hundred files, some local changes, a staged change and a
+// linked worktree, so every benchmarked read has something to report.
+struct Fixture {
+    root: PathBuf,
+    repo: PathBuf,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let root = std::env::temp_dir().join(format!("git-read-bench-{}", std::process::id()));
+        let repo = root.join("repo");
+        fs::create_dir_all(&repo).expect("create fixture directory");
+
+        git(&repo, &["init", "--quiet", "--initial-branch=main"]);
+        git(&repo, &["config", "user.name", "Bench"]);
+        git(&repo, &["config", "user.email", "bench@example.com"]);
+        for dir in 0..10 {
+            let dir_path = repo.join(format!("src/module{}", dir));
+            fs::create_dir_all(&dir_path).expect("create source directory");
+            for file in 0..30 {
+                let content = (0..50)
+                    .map(|line| format!("line {} of file {} in module {}\n", line, file, dir))
+                    .collect::<String>();
+                fs::write(dir_path.join(format!("file{}.rs", file)), content)
+                    .expect("write source file");
+            }
+        }
+        git(&repo, &["add", "--all"]);
+        git(&repo, &["commit", "--quiet", "--message", "Initial commit"]);
+        git(&repo, &["branch", "feature"]);
+        git(
+            &repo,
+            &[
+                "worktree",
+                "add",
+                "--quiet",
+                &root.join("worktree").to_string_lossy(),
+                "feature",
+            ],
+        );
+
+        fs::write(repo.join("src/module0/file0.rs"), "staged\n").expect("write staged change");
+        git(&repo, &["add", "src/module0/file0.rs"]);
+        fs::write(repo.join("src/module1/file0.rs"), "unstaged\n").expect("write change");
+        fs::write(repo.join("untracked.txt"), "untracked\n").expect("write untracked file");
+
+        Self { root, repo }
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .status()
+        .expect("run git");
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+fn git_reads(c: &mut Criterion) {
+    let fixture = Fixture::new();
+    let runtime = Runtime::new().expect("create runtime");
+    let repo = fixture.repo.as_path();
+
+    for (name, backend) in BACKENDS {
+        GitReader::set_backend(backend);
+
+        c.bench_function(&format!("worktree_list/{}", name), |b| {
+            b.iter(|| runtime.block_on(GitService::worktree_list_detailed(repo)))
+        });
+        c.bench_function(&format!("branch_exists/{}", name), |b| {
+            b.iter(|| runtime.block_on(GitService::branch_exists(repo, "feature")))
+        });
+        c.bench_function(&format!("default_branch/{}", name), |b| {
+            b.iter(|| runtime.block_on(GitService::get_default_branch(repo)))
+        });
+        c.bench_function(&format!("status/{}", name), |b| {
+            b.iter(|| runtime.block_on(GitService::status(repo, Some("feature"))))
+        });
+        c.bench_function(&format!("staged_changes/{}", name), |b| {
+            b.iter(|| runtime.block_on(GitService::diff_summary(repo, &["--cached"])))
+        });
+    }
+
+    GitReader::set_backend(GitReadBackend::InProcess);
+}
+
+criterion_group!(benches, git_reads);
+criterion_main!(benches);
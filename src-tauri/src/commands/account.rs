@@ -0,0 +1,56 @@
+use crate::models::GitHubAccount;
+use crate::services::{GitHubClient, StoredAccount, StoredAuth, TokenStore};
+use chrono::Utc;
+use tauri::{command, AppHandle};
+
+#[command]
+pub async fn list_accounts(app: AppHandle) -> Result<Vec<GitHubAccount>, String> {
+    TokenStore::list_account_summaries(&app)
+}
+
+#[command]
+pub async fn switch_account(app: AppHandle, account_id: String) -> Result<(), String> {
+    TokenStore::set_active_account(&app, &account_id)
+}
+
+#[command]
+pub async fn remove_account(app: AppHandle, account_id: String) -> Result<(), String> {
+    TokenStore::remove_account(&app, &account_id)
+}
+
+#[command]
+pub async fn add_token_account(
+    app: AppHandle,
+    host: String,
+    token: String,
+) -> Result<GitHubAccount, String> {
+    let host = host.trim().trim_end_matches('/').to_lowercase();
+    if host.is_empty() || host.contains('/') {
+        return Err(format!("Invalid GitHub host: {}", host));
+    }
+
+    let client = GitHubClient::for_host(&host, token.clone());
+    let user = client.get_authenticated_user().await?;
+
+    let account = StoredAccount {
+        host,
+        auth: StoredAuth {
+            access_token: token,
+            token_type: "bearer".to_string(),
+            scope: String::new(),
+            stored_at: Utc::now().to_rfc3339(),
+            expires_at: None,
+            refresh_token: None,
+            refresh_token_expires_at: None,
+        },
+        user,
+    };
+    TokenStore::save_account(&app, &account)?;
+
+    if TokenStore::active_account(&app)?.is_none() {
+        TokenStore::set_active_account(&app, &account.id())?;
+    }
+
+    let active = TokenStore::active_account(&app)?.map(|a| a.id());
+    Ok(account.to_summary(active.as_deref()))
+}
@@ -0,0 +1,13 @@
+use crate::models::BoardConfig;
+use crate::services::BoardStore;
+use tauri::{command, AppHandle};
+
+#[command]
+pub async fn get_board_config(app: AppHandle) -> Result<BoardConfig, String> {
+    BoardStore::load(&app)
+}
+
+#[command]
+pub async fn save_board_config(app: AppHandle, config: BoardConfig) -> Result<(), String> {
+    BoardStore::save(&app, &config)
+}
@@ -0,0 +1,598 @@
+use crate::commands::worktree::{
+    find_main_repo_from_worktree, repository_account, resolve_base_ref,
+};
+use crate::models::{
+    CommitInfo, CommitResult, DiffOptions, DiffScope, FileChangeSummary, FileContent, FileVersions,
+    PushProgressEvent, PushRejectionReason, PushResult, PushStatus, ResetMode, RevertResult,
+    StructuredDiff,
+};
+use crate::services::{
+    CommitMessage, FileContentLoader, GitCancellation, GitCredentials, GitService, ProgressOutput,
+    RepositoryConfigLoader, WorktreeStore, DEFAULT_REMOTE,
+};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tauri::{command, AppHandle, Emitter};
+
+#[command]
+pub async fn stage_paths(worktree_path: String, paths: Vec<String>) -> Result<(), String> {
+    Ok(GitService::stage_paths(existing_worktree(&worktree_path)?, &paths).await?)
+}
+
+#[command]
+pub async fn unstage_paths(worktree_path: String, paths: Vec<String>) -> Result<(), String> {
+    Ok(GitService::unstage_paths(existing_worktree(&worktree_path)?, &paths).await?)
+}
+
+#[command]
+pub async fn stage_hunk(
+    worktree_path: String,
+    path: String,
+    hunk_index: usize,
+) -> Result<(), String> {
+    let worktree_path = existing_worktree(&worktree_path)?;
+    let diff = GitService::file_diff(worktree_path, &path, false).await?;
+    let patch = hunk_patch(&diff, hunk_index).ok_or_else(|| {
+        format!(
+            "Hunk {} not found in unstaged changes of {}",
+            hunk_index, path
+        )
+    })?;
+
+    Ok(GitService::apply_to_index(worktree_path, &patch, false).await?)
+}
+
+#[command]
+pub async fn unstage_hunk(
+    worktree_path: String,
+    path: String,
+    hunk_index: usize,
+) -> Result<(), String> {
+    let worktree_path = existing_worktree(&worktree_path)?;
+    let diff = GitService::file_diff(worktree_path, &path, true).await?;
+    let patch = hunk_patch(&diff, hunk_index).ok_or_else(|| {
+        format!(
+            "Hunk {} not found in staged changes of {}",
+            hunk_index, path
+        )
+    })?;
+
+    Ok(GitService::apply_to_index(worktree_path, &patch, true).await?)
+}
+
+#[command]
+pub async fn get_staged_changes(worktree_path: String) -> Result<Vec<FileChangeSummary>, String> {
+    Ok(GitService::diff_summary(existing_worktree(&worktree_path)?, &["--cached"]).await?)
+}
+
+#[command]
+pub async fn generate_commit_message(
+    app: AppHandle,
+    worktree_path: String,
+) -> Result<String, String> {
+    let changes =
+        GitService::diff_summary(existing_worktree(&worktree_path)?, &["--cached"]).await?;
+    if changes.is_empty() {
+        return Err("Nothing is staged".to_string());
+    }
+
+    let issue_number = WorktreeStore::find_by_path(&app, &worktree_path)?
+        .and_then(|worktree| worktree.issue_number);
+
+    Ok(CommitMessage::generate(&changes, issue_number))
+}
+
+#[command]
+pub async fn lint_commit_message(
+    app: AppHandle,
+    worktree_path: String,
+    message: String,
+) -> Result<Vec<String>, String> {
+    let config = RepositoryConfigLoader::load(&app, existing_worktree(&worktree_path)?)?;
+
+    Ok(CommitMessage::lint(&message, &config.commit))
+}
+
+#[command]
+pub async fn commit_changes(
+    app: AppHandle,
+    worktree_path: String,
+    message: String,
+) -> Result<CommitResult, String> {
+    let path = existing_worktree(&worktree_path)?;
+    let config = RepositoryConfigLoader::load(&app, path)?;
+
+    let errors = CommitMessage::lint(&message, &config.commit);
+    if !errors.is_empty() {
+        return Err(format!("Commit message rejected:\n{}", errors.join("\n")));
+    }
+    if GitService::diff_summary(path, &["--cached"])
+        .await?
+        .is_empty()
+    {
+        return Err("Nothing is staged".to_string());
+    }
+
+    let sha = GitService::commit(path, &message).await?;
+
+    Ok(CommitResult {
+        sha,
+        subject: message.lines().next().unwrap_or_default().to_string(),
+    })
+}
+
+#[command]
+pub async fn push_worktree_branch(
+    app: AppHandle,
+    worktree_path: String,
+    force_with_lease: Option<bool>,
+    operation_id: Option<String>,
+) -> Result<PushResult, String> {
+    let path = PathBuf::from(existing_worktree(&worktree_path)?);
+    let branch = GitService::current_branch(&path)
+        .await?
+        .ok_or_else(|| "Cannot push a detached HEAD".to_string())?;
+
+    let repo_path = find_main_repo_from_worktree(&path).await?;
+    let credentials = repository_account(&app, &repo_path)
+        .await?
+        .map(|account| GitCredentials::new(account.auth.access_token))
+        .transpose()?;
+
+    let output = GitCancellation::scope(
+        operation_id,
+        GitService::push(
+            &path,
+            credentials.as_ref(),
+            DEFAULT_REMOTE,
+            &branch,
+            force_with_lease.unwrap_or(false),
+            |phase, percent, message| {
+                let event = PushProgressEvent {
+                    worktree_path: worktree_path.clone(),
+                    phase: phase.to_string(),
+                    percent,
+                    message: message.to_string(),
+                };
+                if let Err(e) = app.emit("push-progress", event) {
+                    eprintln!("Failed to emit push-progress event: {}", e);
+                }
+            },
+        ),
+    )
+    .await?;
+
+    push_result(branch, &output)
+}
+
+// Stops the git commands of an operation started with that `operation_id`. Returns false
+// when it already finished.
+#[command]
+pub async fn cancel_git_operation(operation_id: String) -> Result<bool, String> {
+    Ok(GitCancellation::cancel(&operation_id))
+}
+
+// Both full versions of a file for a side-by-side diff: at `base_ref` (the merge-base
+// with the worktree's base branch by default) and in the working tree.
+#[command]
+pub async fn get_file_versions(
+    app: AppHandle,
+    worktree_path: String,
+    path: String,
+    old_path: Option<String>,
+    base_ref: Option<String>,
+) -> Result<FileVersions, String> {
+    let worktree = PathBuf::from(existing_worktree(&worktree_path)?);
+    check_relative_path(&path)?;
+    if let Some(ref old_path) = old_path {
+        check_relative_path(old_path)?;
+    }
+
+    let base_ref = match base_ref {
+        Some(base_ref) => base_ref,
+        None => {
+            let base_branch = resolve_base_ref(&app, &worktree).await?;
+            GitService::merge_base(&worktree, &base_branch, "HEAD")
+                .await
+                .unwrap_or(base_branch)
+        }
+    };
+
+    let base_path = old_path.as_deref().unwrap_or(&path);
+    let base = match GitService::blob_size(&worktree, &base_ref, base_path).await? {
+        Some(size) if size > FileContentLoader::size_limit(base_path) => {
+            Some(FileContentLoader::too_large(base_path, size))
+        }
+        Some(_) => {
+            let bytes = GitService::read_blob(&worktree, &base_ref, base_path).await?;
+            Some(FileContentLoader::from_bytes(base_path, &bytes))
+        }
+        None => None,
+    };
+
+    Ok(FileVersions {
+        working: working_file(&worktree.join(&path), &path)?,
+        base,
+        path,
+        old_path,
+        base_ref,
+    })
+}
+
+// Symlinks are not followed: they may point outside the worktree, and git compares their
+// target path rather than the file behind it.
+fn working_file(file: &Path, path: &str) -> Result<Option<FileContent>, String> {
+    let metadata = match fs::symlink_metadata(file) {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            let target =
+                fs::read_link(file).map_err(|e| format!("Failed to read {:?}: {}", file, e))?;
+            return Ok(Some(FileContentLoader::symlink(&target)));
+        }
+        Ok(metadata) if metadata.is_file() => metadata,
+        _ => return Ok(None),
+    };
+    if metadata.len() > FileContentLoader::size_limit(path) {
+        return Ok(Some(FileContentLoader::too_large(path, metadata.len())));
+    }
+
+    let bytes = fs::read(file).map_err(|e| format!("Failed to read {:?}: {}", file, e))?;
+    Ok(Some(FileContentLoader::from_bytes(path, &bytes)))
+}
+
+// Paths come from the frontend and must stay inside the worktree.
+fn check_relative_path(path: &str) -> Result<(), String> {
+    let valid = !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid path: {:?}", path))
+    }
+}
+
+// Commits on the worktree branch that are not on its base branch, newest first.
+#[command]
+pub async fn get_worktree_commits(
+    app: AppHandle,
+    worktree_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<CommitInfo>, String> {
+    let worktree = PathBuf::from(existing_worktree(&worktree_path)?);
+    let base_ref = resolve_base_ref(&app, &worktree).await?;
+
+    Ok(GitService::log(&worktree, &format!("{}..HEAD", base_ref), limit).await?)
+}
+
+#[command]
+pub async fn get_commit_diff(
+    worktree_path: String,
+    sha: String,
+    options: Option<DiffOptions>,
+) -> Result<StructuredDiff, String> {
+    let worktree = existing_worktree(&worktree_path)?;
+    let options = options.unwrap_or_default();
+
+    let scope = DiffScope::Commit { sha };
+    let args = GitService::diff_scope_args(worktree, &scope, "HEAD", &[]).await?;
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    Ok(GitService::structured_diff(worktree, &args, &options).await?)
+}
+
+// Conflicts leave the revert in progress; it is resumed or abandoned with
+// `continue_worktree_sync` / `abort_worktree_sync`.
+#[command]
+pub async fn revert_commit(worktree_path: String, sha: String) -> Result<RevertResult, String> {
+    let worktree = existing_worktree(&worktree_path)?;
+    ensure_idle(worktree).await?;
+    if GitService::has_uncommitted_changes(worktree).await? {
+        return Err("Commit or discard uncommitted changes before reverting".to_string());
+    }
+
+    if GitService::revert(worktree, &sha).await? {
+        return Ok(RevertResult {
+            sha: Some(GitService::rev_parse(worktree, "HEAD").await?),
+            operation: None,
+            conflicts: vec![],
+        });
+    }
+
+    Ok(RevertResult {
+        sha: None,
+        operation: GitService::operation_in_progress(worktree).await?,
+        conflicts: GitService::conflicts(worktree).await?,
+    })
+}
+
+// Moves the branch back to one of its own commits. A hard reset would discard
+// uncommitted work, so it is refused while there is any.
+#[command]
+pub async fn reset_to_commit(
+    worktree_path: String,
+    sha: String,
+    mode: ResetMode,
+) -> Result<String, String> {
+    let worktree = existing_worktree(&worktree_path)?;
+    ensure_idle(worktree).await?;
+
+    let commit = GitService::rev_parse(worktree, &format!("{}^{{commit}}", sha))
+        .await
+        .map_err(|_| format!("Unknown commit: {}", sha))?;
+    if !GitService::is_ancestor(worktree, &commit, "HEAD").await? {
+        return Err(format!("{} is not part of the current branch", sha));
+    }
+    if mode == ResetMode::Hard && GitService::has_uncommitted_changes(worktree).await? {
+        return Err("Commit or discard uncommitted changes before a hard reset".to_string());
+    }
+
+    GitService::reset(worktree, &commit, mode).await?;
+    Ok(commit)
+}
+
+fn existing_worktree(worktree_path: &str) -> Result<&Path, String> {
+    let path = Path::new(worktree_path);
+
+    if !path.exists() {
+        return Err(format!("Worktree path does not exist: {:?}", path));
+    }
+
+    Ok(path)
+}
+
+async fn ensure_idle(worktree_path: &Path) -> Result<(), String> {
+    match GitService::operation_in_progress(worktree_path).await? {
+        Some(operation) => Err(format!(
+            "A {:?} is already in progress; continue or abort it first",
+            operation
+        )),
+        None => Ok(()),
+    }
+}
+
+// Rebuilds a single-hunk patch (file header + the selected hunk) from a one-file diff.
+// Works on bytes so content in other encodings is applied back unchanged.
+fn hunk_patch(diff: &[u8], hunk_index: usize) -> Option<Vec<u8>> {
+    let mut header = Vec::new();
+    let mut hunks: Vec<Vec<u8>> = Vec::new();
+
+    for line in diff.split_inclusive(|&byte| byte == b'\n') {
+        if line.starts_with(b"@@") {
+            hunks.push(line.to_vec());
+        } else if let Some(hunk) = hunks.last_mut() {
+            hunk.extend_from_slice(line);
+        } else {
+            header.extend_from_slice(line);
+        }
+    }
+
+    hunks
+        .get(hunk_index)
+        .map(|hunk| [header.as_slice(), hunk].concat())
+}
+
+// Reads the ref status line of `git push --porcelain`:
+// "<flag>\t<from>:<to>\t<summary> (<reason>)", where the flag is ' ' (fast-forward),
+// '+' (forced), '*' (new), '=' (up to date) or '!' (rejected).
+fn push_result(branch: String, output: &ProgressOutput) -> Result<PushResult, String> {
+    let status_line = output.stdout.lines().find(|line| {
+        line.split('\t').count() == 3 && line.split('\t').nth(1).is_some_and(|r| r.contains(':'))
+    });
+
+    let Some(line) = status_line else {
+        // No ref was processed: the push failed before talking refs (auth, network, ...).
+        let message = output.messages.join("\n");
+        if output.success {
+            return Err(format!("Unexpected push output: {}", output.stdout.trim()));
+        }
+        return Err(if message.is_empty() {
+            "Push failed".to_string()
+        } else {
+            message
+        });
+    };
+
+    let mut fields = line.split('\t');
+    let flag = fields.next().unwrap_or_default();
+    let summary = fields.nth(1).unwrap_or_default().to_string();
+
+    let status = match flag {
+        "*" => PushStatus::Created,
+        "+" => PushStatus::ForceUpdated,
+        "=" => PushStatus::UpToDate,
+        "!" => PushStatus::Rejected,
+        _ => PushStatus::Updated,
+    };
+
+    let rejection = (status == PushStatus::Rejected).then(|| {
+        let details = format!("{}\n{}", summary, output.messages.join("\n")).to_lowercase();
+        if details.contains("stale info") {
+            PushRejectionReason::StaleLease
+        } else if details.contains("non-fast-forward") || details.contains("fetch first") {
+            PushRejectionReason::NonFastForward
+        } else if details.contains("protected branch") || details.contains("gh006") {
+            PushRejectionReason::ProtectedBranch
+        } else if details.contains("permission") || details.contains("403") {
+            PushRejectionReason::PermissionDenied
+        } else {
+            PushRejectionReason::Other
+        }
+    });
+
+    let message = if status == PushStatus::Rejected {
+        Some(
+            std::iter::once(summary)
+                .chain(output.messages.iter().cloned())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    } else {
+        None
+    };
+
+    Ok(PushResult {
+        branch,
+        remote: DEFAULT_REMOTE.to_string(),
+        status,
+        rejection,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patch(lines: &[&[u8]]) -> Vec<u8> {
+        lines
+            .iter()
+            .flat_map(|line| [*line, b"\n"])
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    fn two_hunk_diff() -> Vec<u8> {
+        patch(&[
+            b"diff --git a/f b/f",
+            b"index 9a963dd..81e1d04 100644",
+            b"--- a/f",
+            b"+++ b/f",
+            b"@@ -1,2 +1,2 @@",
+            b"-caf\xe9",
+            b"+caf\xe9!",
+            b" b",
+            b"@@ -9,2 +9,2 @@ h",
+            b" i",
+            b"-j",
+            b"+j!",
+        ])
+    }
+
+    #[test]
+    fn hunk_patch_keeps_the_header_and_one_hunk() {
+        assert_eq!(
+            hunk_patch(&two_hunk_diff(), 1),
+            Some(patch(&[
+                b"diff --git a/f b/f",
+                b"index 9a963dd..81e1d04 100644",
+                b"--- a/f",
+                b"+++ b/f",
+                b"@@ -9,2 +9,2 @@ h",
+                b" i",
+                b"-j",
+                b"+j!",
+            ]))
+        );
+        assert_eq!(hunk_patch(&two_hunk_diff(), 2), None);
+    }
+
+    #[test]
+    fn hunk_patch_keeps_non_utf8_bytes() {
+        let hunk = hunk_patch(&two_hunk_diff(), 0).unwrap();
+        assert!(hunk.ends_with(&patch(&[
+            b"@@ -1,2 +1,2 @@",
+            b"-caf\xe9",
+            b"+caf\xe9!",
+            b" b"
+        ])));
+    }
+
+    fn push_output(success: bool, stdout: &str, messages: &[&str]) -> ProgressOutput {
+        ProgressOutput {
+            success,
+            code: Some(if success { 0 } else { 1 }),
+            stdout: stdout.to_string(),
+            messages: messages.iter().map(|message| message.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn push_result_reads_ref_status() {
+        let cases = [
+            ("*\tHEAD:refs/heads/main\t[new branch]", PushStatus::Created),
+            (
+                " \tHEAD:refs/heads/main\t707bdcf..9a7beab",
+                PushStatus::Updated,
+            ),
+            (
+                "=\tHEAD:refs/heads/main\t[up to date]",
+                PushStatus::UpToDate,
+            ),
+            (
+                "+\tHEAD:refs/heads/main\t9a7beab...3921f51 (forced update)",
+                PushStatus::ForceUpdated,
+            ),
+        ];
+        for (line, status) in cases {
+            let stdout = format!("To /tmp/remote.git\n{}\nDone\n", line);
+            let result = push_result("main".to_string(), &push_output(true, &stdout, &[])).unwrap();
+            assert_eq!(result.status, status, "{}", line);
+            assert_eq!(result.rejection, None);
+            assert_eq!(result.message, None);
+        }
+    }
+
+    #[test]
+    fn push_result_classifies_rejections() {
+        let cases = [
+            (
+                "[rejected] (non-fast-forward)",
+                PushRejectionReason::NonFastForward,
+            ),
+            (
+                "[rejected] (fetch first)",
+                PushRejectionReason::NonFastForward,
+            ),
+            ("[rejected] (stale info)", PushRejectionReason::StaleLease),
+            (
+                "[remote rejected] (pre-receive hook declined)",
+                PushRejectionReason::Other,
+            ),
+        ];
+        for (summary, reason) in cases {
+            let stdout = format!(
+                "To /tmp/remote.git\n!\tHEAD:refs/heads/main\t{}\nDone\n",
+                summary
+            );
+            let output = push_output(
+                false,
+                &stdout,
+                &["error: failed to push some refs to '/tmp/remote.git'"],
+            );
+            let result = push_result("main".to_string(), &output).unwrap();
+            assert_eq!(result.status, PushStatus::Rejected);
+            assert_eq!(result.rejection, Some(reason), "{}", summary);
+            assert!(result.message.unwrap().starts_with(summary));
+        }
+    }
+
+    #[test]
+    fn push_result_reports_protected_branches_from_messages() {
+        let stdout = "To github.com:o/r.git\n!\tHEAD:refs/heads/main\t[remote rejected] (protected branch hook declined)\nDone\n";
+        let output = push_output(
+            false,
+            stdout,
+            &["remote: error: GH006: Protected branch update failed for refs/heads/main."],
+        );
+        let result = push_result("main".to_string(), &output).unwrap();
+        assert_eq!(result.rejection, Some(PushRejectionReason::ProtectedBranch));
+    }
+
+    #[test]
+    fn push_result_fails_without_a_ref_status() {
+        let output = push_output(
+            false,
+            "",
+            &["fatal: could not read Username for 'https://github.com': terminal prompts disabled"],
+        );
+        assert_eq!(
+            push_result("main".to_string(), &output).unwrap_err(),
+            "fatal: could not read Username for 'https://github.com': terminal prompts disabled"
+        );
+        assert_eq!(
+            push_result("main".to_string(), &push_output(false, "", &[])).unwrap_err(),
+            "Push failed"
+        );
+    }
+}
@@ -1,17 +1,101 @@
 use crate::models::{
-    CreatePullRequestRequest, Issue, IssueSortField, Label, ListIssuesParams,
-    ListIssuesStateFilter, Milestone, PullRequest, SortDirection,
+    ActingAs, CreateIssueCommentRequest, CreatePullRequestRequest, Issue, IssueComment,
+    IssueSortField, Label, ListIssuesParams, ListIssuesStateFilter, Milestone, PullRequest,
+    SortDirection,
+};
+use crate::services::{
+    is_unauthorized_error, GitHubAppAuth, GitHubClient, GitHubUser, IssueCache, OAuthConfig,
+    RepositoryStore, StoredAccount, StoredAuth, TokenRefresher, TokenStore, WorktreeStore,
+    DEFAULT_GITHUB_HOST,
 };
-use crate::services::{GitHubClient, GitHubUser, OAuthConfig, StoredAuth, TokenStore};
 use chrono::Utc;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Mutex;
 use tauri::{command, AppHandle};
 
 static PENDING_STATES: Lazy<Mutex<HashMap<String, chrono::DateTime<Utc>>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+fn client_for(account: &StoredAccount) -> GitHubClient {
+    GitHubClient::for_host(&account.host, account.auth.access_token.clone())
+}
+
+pub(crate) async fn with_github_client<T, F, Fut>(
+    app: &AppHandle,
+    account_id: Option<&str>,
+    request: F,
+) -> Result<T, String>
+where
+    F: Fn(GitHubClient) -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    let account = TokenRefresher::account(app, account_id).await?;
+
+    match request(client_for(&account)).await {
+        Err(e) if is_unauthorized_error(&e) => {
+            let account = TokenRefresher::refresh_after_unauthorized(
+                app,
+                &account.id(),
+                &account.auth.access_token,
+            )
+            .await?;
+            request(client_for(&account)).await
+        }
+        result => result,
+    }
+}
+
+async fn with_installation_client<T, F, Fut>(
+    app: &AppHandle,
+    owner: &str,
+    repo: &str,
+    request: F,
+) -> Result<T, String>
+where
+    F: Fn(GitHubClient) -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    let credentials = GitHubAppAuth::installation_token(app, owner, repo).await?;
+
+    match request(GitHubClient::for_host(&credentials.host, credentials.token)).await {
+        Err(e) if is_unauthorized_error(&e) => {
+            GitHubAppAuth::invalidate_token(credentials.installation_id).await;
+            let credentials = GitHubAppAuth::installation_token(app, owner, repo).await?;
+            request(GitHubClient::for_host(&credentials.host, credentials.token)).await
+        }
+        result => result,
+    }
+}
+
+async fn with_repository_client<T, F, Fut>(
+    app: &AppHandle,
+    owner: &str,
+    repo: &str,
+    acting_as: ActingAs,
+    request: F,
+) -> Result<T, String>
+where
+    F: Fn(GitHubClient) -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    match acting_as {
+        ActingAs::User => {
+            let account_id = repository_account_id(app, owner, repo);
+            with_github_client(app, account_id.as_deref(), request).await
+        }
+        ActingAs::Installation => with_installation_client(app, owner, repo, request).await,
+    }
+}
+
+fn repository_account_id(app: &AppHandle, owner: &str, repo: &str) -> Option<String> {
+    RepositoryStore::find_by_name(app, owner, repo)
+        .ok()
+        .flatten()
+        .and_then(|repository| repository.account_id)
+}
+
 #[command]
 pub async fn start_oauth_flow(_app: AppHandle) -> Result<String, String> {
     let config = OAuthConfig::from_env()?;
@@ -57,36 +141,28 @@ pub async fn exchange_oauth_code(
     let config = OAuthConfig::from_env()?;
     let token_response = config.exchange_code(&code).await?;
 
-    let stored_auth = StoredAuth {
-        access_token: token_response.access_token.clone(),
-        token_type: token_response.token_type,
-        scope: token_response.scope,
-        stored_at: Utc::now().to_rfc3339(),
-    };
-    TokenStore::save_token(&app, &stored_auth)?;
+    let auth = StoredAuth::from_token_response(token_response);
 
-    let client = GitHubClient::with_token(token_response.access_token);
+    let client = GitHubClient::with_token(auth.access_token.clone());
     let user = client.get_authenticated_user().await?;
 
-    TokenStore::save_user(&app, &user)?;
+    let account = StoredAccount {
+        host: DEFAULT_GITHUB_HOST.to_string(),
+        auth,
+        user: user.clone(),
+    };
+    TokenStore::save_account(&app, &account)?;
+    TokenStore::set_active_account(&app, &account.id())?;
 
     Ok(user)
 }
 
 #[command]
 pub async fn get_authenticated_user(app: AppHandle) -> Result<Option<GitHubUser>, String> {
-    if let Some(user) = TokenStore::load_user(&app)? {
-        return Ok(Some(user));
-    }
-
-    if let Some(auth) = TokenStore::load_token(&app)? {
-        let client = GitHubClient::with_token(auth.access_token);
-        let user = client.get_authenticated_user().await?;
-        TokenStore::save_user(&app, &user)?;
-        return Ok(Some(user));
+    if let Err(e) = TokenRefresher::migrate_legacy_account(&app).await {
+        eprintln!("Failed to migrate legacy GitHub token: {}", e);
     }
-
-    Ok(None)
+    TokenStore::load_user(&app)
 }
 
 #[command]
@@ -97,10 +173,9 @@ pub async fn logout(app: AppHandle) -> Result<(), String> {
 
 #[command]
 pub async fn get_stored_token(app: AppHandle) -> Result<Option<String>, String> {
-    match TokenStore::load_token(&app)? {
-        Some(auth) => Ok(Some(auth.access_token)),
-        None => Ok(None),
-    }
+    Ok(TokenRefresher::load_fresh(&app, None)
+        .await?
+        .map(|account| account.auth.access_token))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -119,10 +194,6 @@ pub async fn list_issues(
     page: Option<u32>,
     exclude_pull_requests: Option<bool>,
 ) -> Result<Vec<Issue>, String> {
-    let auth = TokenStore::load_token(&app)?.ok_or_else(|| "Not authenticated".to_string())?;
-
-    let client = GitHubClient::with_token(auth.access_token);
-
     let params = ListIssuesParams {
         state,
         labels,
@@ -134,7 +205,16 @@ pub async fn list_issues(
         page,
     };
 
-    let issues = client.list_issues(&owner, &repo, &params).await?;
+    let account_id = repository_account_id(&app, &owner, &repo);
+    let (owner, repo, params) = (&owner, &repo, &params);
+    let issues = with_github_client(&app, account_id.as_deref(), |client| async move {
+        client.list_issues(owner, repo, params).await
+    })
+    .await?;
+
+    if let Err(e) = IssueCache::store(&app, &format!("{}/{}", owner, repo), &issues) {
+        eprintln!("Failed to cache issues for {}/{}: {}", owner, repo, e);
+    }
 
     if exclude_pull_requests.unwrap_or(false) {
         Ok(issues
@@ -146,6 +226,15 @@ pub async fn list_issues(
     }
 }
 
+#[command]
+pub async fn get_cached_issues(
+    app: AppHandle,
+    owner: String,
+    repo: String,
+) -> Result<Vec<Issue>, String> {
+    IssueCache::load(&app, &format!("{}/{}", owner, repo))
+}
+
 #[command]
 pub async fn get_issue(
     app: AppHandle,
@@ -153,10 +242,12 @@ pub async fn get_issue(
     repo: String,
     issue_number: i32,
 ) -> Result<Issue, String> {
-    let auth = TokenStore::load_token(&app)?.ok_or_else(|| "Not authenticated".to_string())?;
-
-    let client = GitHubClient::with_token(auth.access_token);
-    client.get_issue(&owner, &repo, issue_number).await
+    let account_id = repository_account_id(&app, &owner, &repo);
+    let (owner, repo) = (&owner, &repo);
+    with_github_client(&app, account_id.as_deref(), |client| async move {
+        client.get_issue(owner, repo, issue_number).await
+    })
+    .await
 }
 
 #[command]
@@ -165,10 +256,12 @@ pub async fn list_labels(
     owner: String,
     repo: String,
 ) -> Result<Vec<Label>, String> {
-    let auth = TokenStore::load_token(&app)?.ok_or_else(|| "Not authenticated".to_string())?;
-
-    let client = GitHubClient::with_token(auth.access_token);
-    client.list_labels(&owner, &repo).await
+    let account_id = repository_account_id(&app, &owner, &repo);
+    let (owner, repo) = (&owner, &repo);
+    with_github_client(&app, account_id.as_deref(), |client| async move {
+        client.list_labels(owner, repo).await
+    })
+    .await
 }
 
 #[command]
@@ -177,10 +270,12 @@ pub async fn list_milestones(
     owner: String,
     repo: String,
 ) -> Result<Vec<Milestone>, String> {
-    let auth = TokenStore::load_token(&app)?.ok_or_else(|| "Not authenticated".to_string())?;
-
-    let client = GitHubClient::with_token(auth.access_token);
-    client.list_milestones(&owner, &repo).await
+    let account_id = repository_account_id(&app, &owner, &repo);
+    let (owner, repo) = (&owner, &repo);
+    with_github_client(&app, account_id.as_deref(), |client| async move {
+        client.list_milestones(owner, repo).await
+    })
+    .await
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -194,11 +289,8 @@ pub async fn create_pull_request(
     head: String,
     base: String,
     draft: Option<bool>,
+    acting_as: Option<ActingAs>,
 ) -> Result<PullRequest, String> {
-    let auth = TokenStore::load_token(&app)?.ok_or_else(|| "Not authenticated".to_string())?;
-
-    let client = GitHubClient::with_token(auth.access_token);
-
     let request = CreatePullRequestRequest {
         title,
         body,
@@ -207,5 +299,71 @@ pub async fn create_pull_request(
         draft,
     };
 
-    client.create_pull_request(&owner, &repo, &request).await
+    let (owner, repo, request) = (&owner, &repo, &request);
+    let pull_request = with_repository_client(
+        &app,
+        owner,
+        repo,
+        acting_as.unwrap_or_default(),
+        |client| async move { client.create_pull_request(owner, repo, request).await },
+    )
+    .await?;
+
+    if let Err(e) = link_pull_request_to_worktree(&app, owner, repo, &request.head, &pull_request) {
+        eprintln!("Failed to link pull request to worktree: {}", e);
+    }
+
+    Ok(pull_request)
+}
+
+fn link_pull_request_to_worktree(
+    app: &AppHandle,
+    owner: &str,
+    repo: &str,
+    head: &str,
+    pull_request: &PullRequest,
+) -> Result<(), String> {
+    let repository = match RepositoryStore::find_by_name(app, owner, repo)? {
+        Some(repository) => repository,
+        None => return Ok(()),
+    };
+    // Cross-repository heads are written as "owner:branch".
+    let branch = head.rsplit_once(':').map_or(head, |(_, branch)| branch);
+
+    if let Some(worktree) = WorktreeStore::find_by_branch(app, &repository.local_path, branch)? {
+        WorktreeStore::link_pull_request(
+            app,
+            &worktree.id,
+            pull_request.number,
+            &pull_request.html_url,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[command]
+pub async fn create_issue_comment(
+    app: AppHandle,
+    owner: String,
+    repo: String,
+    issue_number: i32,
+    body: String,
+    acting_as: Option<ActingAs>,
+) -> Result<IssueComment, String> {
+    let request = CreateIssueCommentRequest { body };
+
+    let (owner, repo, request) = (&owner, &repo, &request);
+    with_repository_client(
+        &app,
+        owner,
+        repo,
+        acting_as.unwrap_or_default(),
+        |client| async move {
+            client
+                .create_issue_comment(owner, repo, issue_number, request)
+                .await
+        },
+    )
+    .await
 }
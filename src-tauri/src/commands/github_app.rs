@@ -0,0 +1,36 @@
+use crate::models::GitHubAppConfig;
+use crate::services::{GitHubAppAuth, DEFAULT_GITHUB_HOST};
+use tauri::{command, AppHandle};
+
+#[command]
+pub async fn get_github_app_config(app: AppHandle) -> Result<Option<GitHubAppConfig>, String> {
+    GitHubAppAuth::load_config(&app)
+}
+
+#[command]
+pub async fn configure_github_app(
+    app: AppHandle,
+    app_id: String,
+    private_key_path: String,
+    host: Option<String>,
+    installation_id: Option<i64>,
+) -> Result<GitHubAppConfig, String> {
+    let config = GitHubAppConfig {
+        app_id: app_id.trim().to_string(),
+        private_key_path,
+        host: host
+            .map(|h| h.trim().trim_end_matches('/').to_lowercase())
+            .filter(|h| !h.is_empty())
+            .unwrap_or_else(|| DEFAULT_GITHUB_HOST.to_string()),
+        installation_id,
+    };
+
+    GitHubAppAuth::save_config(&app, &config).await?;
+
+    Ok(config)
+}
+
+#[command]
+pub async fn clear_github_app_config(app: AppHandle) -> Result<(), String> {
+    GitHubAppAuth::clear_config(&app).await
+}
@@ -1,11 +1,23 @@
+pub mod account;
 pub mod agent;
+pub mod board;
+pub mod git;
 pub mod github;
+pub mod github_app;
+pub mod settings;
 pub mod shell;
 pub mod storage;
+pub mod workspace;
 pub mod worktree;
 
+pub use account::*;
 pub use agent::*;
+pub use board::*;
+pub use git::*;
 pub use github::*;
+pub use github_app::*;
+pub use settings::*;
 pub use shell::*;
 pub use storage::*;
+pub use workspace::*;
 pub use worktree::*;
@@ -0,0 +1,17 @@
+use crate::models::Settings;
+use crate::services::SettingsStore;
+use serde_json::{Map, Value};
+use tauri::{command, AppHandle};
+
+#[command]
+pub async fn get_settings(app: AppHandle) -> Result<Settings, String> {
+    SettingsStore::load(&app)
+}
+
+#[command]
+pub async fn update_settings(
+    app: AppHandle,
+    patch: Map<String, Value>,
+) -> Result<Settings, String> {
+    SettingsStore::update(&app, patch)
+}
@@ -1,6 +1,16 @@
-use crate::models::Repository;
-use crate::services::RepositoryStore;
-use tauri::{command, AppHandle};
+use crate::commands::github::with_github_client;
+use crate::models::{
+    CloneOptions, CloneProgressEvent, DiscoveredRepository, Repository, RepositoryConfig,
+};
+use crate::services::{
+    GitCancellation, GitCredentials, GitService, RepositoryConfigLoader, RepositoryDiscovery,
+    RepositoryStore, StoredAccount, TokenRefresher, TokenStore, DEFAULT_DISCOVERY_DEPTH,
+};
+use chrono::Utc;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, Emitter};
+use uuid::Uuid;
 
 #[command]
 pub async fn save_repository(app: AppHandle, repository: Repository) -> Result<(), String> {
@@ -16,3 +26,187 @@ pub async fn load_repositories(app: AppHandle) -> Result<Vec<Repository>, String
 pub async fn delete_repository(app: AppHandle, id: String) -> Result<(), String> {
     RepositoryStore::delete(&app, &id)
 }
+
+#[command]
+pub async fn clone_repository(
+    app: AppHandle,
+    owner: String,
+    name: String,
+    parent_dir: String,
+    options: Option<CloneOptions>,
+    account_id: Option<String>,
+    operation_id: Option<String>,
+) -> Result<Repository, String> {
+    let options = options.unwrap_or_default();
+    let parent_dir = PathBuf::from(&parent_dir);
+
+    if !parent_dir.is_dir() {
+        return Err(format!("Parent directory does not exist: {:?}", parent_dir));
+    }
+    if options.depth == Some(0) {
+        return Err("Clone depth must be at least 1".to_string());
+    }
+
+    let (owner_ref, name_ref) = (&owner, &name);
+    let info = with_github_client(&app, account_id.as_deref(), |client| async move {
+        client.get_repository(owner_ref, name_ref).await
+    })
+    .await?;
+
+    let destination = parent_dir.join(&info.name);
+    if destination.exists() {
+        return Err(format!("Destination already exists: {:?}", destination));
+    }
+
+    let account = TokenRefresher::account(&app, account_id.as_deref()).await?;
+    let credentials = GitCredentials::new(account.auth.access_token.clone())?;
+
+    let full_name = info.full_name.clone();
+    let result = GitCancellation::scope(
+        operation_id,
+        GitService::clone(
+            &credentials,
+            &info.clone_url,
+            &destination,
+            &options,
+            |phase, percent, message| {
+                let event = CloneProgressEvent {
+                    full_name: full_name.clone(),
+                    phase: phase.to_string(),
+                    percent,
+                    message: message.to_string(),
+                };
+                if let Err(e) = app.emit("clone-progress", event) {
+                    eprintln!("Failed to emit clone-progress event: {}", e);
+                }
+            },
+        ),
+    )
+    .await;
+
+    if let Err(e) = result {
+        if destination.exists() {
+            let _ = std::fs::remove_dir_all(&destination);
+        }
+        return Err(format!("Failed to clone {}: {}", info.full_name, e));
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let repository = Repository {
+        id: Uuid::new_v4().to_string(),
+        owner: info.owner.login,
+        name: info.name,
+        full_name: info.full_name,
+        local_path: destination.to_string_lossy().to_string(),
+        default_branch: info.default_branch,
+        is_private: info.private,
+        account_id: Some(account.id()),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    RepositoryStore::save(&app, repository.clone())?;
+
+    Ok(repository)
+}
+
+#[command]
+pub async fn discover_repositories(
+    app: AppHandle,
+    root_dir: String,
+    max_depth: Option<usize>,
+) -> Result<Vec<DiscoveredRepository>, String> {
+    let hosts: HashSet<String> = TokenStore::list_accounts(&app)?
+        .into_iter()
+        .map(|account| account.host.to_ascii_lowercase())
+        .collect();
+    let registered = RepositoryStore::load_all(&app)?;
+    let max_depth = max_depth.unwrap_or(DEFAULT_DISCOVERY_DEPTH);
+
+    RepositoryDiscovery::scan(Path::new(&root_dir), max_depth, &hosts, &registered).await
+}
+
+#[command]
+pub async fn register_repositories(
+    app: AppHandle,
+    candidates: Vec<DiscoveredRepository>,
+) -> Result<Vec<Repository>, String> {
+    let accounts = TokenStore::list_accounts(&app)?;
+    let active_id = TokenStore::active_account(&app)?.map(|account| account.id());
+    let mut registered = Vec::new();
+
+    for candidate in candidates {
+        if candidate.registered_id.is_some()
+            || RepositoryStore::find_by_name(&app, &candidate.owner, &candidate.name)?.is_some()
+        {
+            continue;
+        }
+
+        let account_id = account_for_host(&accounts, active_id.as_deref(), &candidate.host);
+        let (owner, name) = (&candidate.owner, &candidate.name);
+        // Metadata is best-effort: a repository the account cannot see is still registered
+        // with what the local clone knows.
+        let info = match account_id {
+            Some(ref account_id) => {
+                with_github_client(&app, Some(account_id), |client| async move {
+                    client.get_repository(owner, name).await
+                })
+                .await
+                .map_err(|e| eprintln!("Failed to fetch {}: {}", candidate.full_name, e))
+                .ok()
+            }
+            None => None,
+        };
+
+        let now = Utc::now().to_rfc3339();
+        let repository = Repository {
+            id: Uuid::new_v4().to_string(),
+            owner: candidate.owner.clone(),
+            name: candidate.name.clone(),
+            full_name: candidate.full_name.clone(),
+            local_path: candidate.local_path,
+            default_branch: candidate
+                .default_branch
+                .or_else(|| info.as_ref().map(|i| i.default_branch.clone()))
+                .unwrap_or_else(|| "main".to_string()),
+            is_private: info.as_ref().map(|i| i.private).unwrap_or(false),
+            account_id,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        RepositoryStore::save(&app, repository.clone())?;
+        registered.push(repository);
+    }
+
+    Ok(registered)
+}
+
+fn account_for_host(
+    accounts: &[StoredAccount],
+    active_id: Option<&str>,
+    host: &str,
+) -> Option<String> {
+    let matching: Vec<&StoredAccount> = accounts
+        .iter()
+        .filter(|account| account.host.eq_ignore_ascii_case(host))
+        .collect();
+
+    matching
+        .iter()
+        .find(|account| Some(account.id().as_str()) == active_id)
+        .or_else(|| matching.first())
+        .map(|account| account.id())
+}
+
+#[command]
+pub async fn get_repository_config(
+    app: AppHandle,
+    repo_path: String,
+) -> Result<RepositoryConfig, String> {
+    let repo_path = Path::new(&repo_path);
+
+    if !repo_path.exists() {
+        return Err(format!("Repository path does not exist: {:?}", repo_path));
+    }
+
+    RepositoryConfigLoader::load(&app, repo_path)
+}
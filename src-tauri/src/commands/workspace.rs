@@ -0,0 +1,26 @@
+use crate::models::{PathRemap, WorkspaceImportReport};
+use crate::services::WorkspaceService;
+use std::path::Path;
+use tauri::{command, AppHandle};
+
+#[command]
+pub async fn export_workspace(app: AppHandle, path: String) -> Result<(), String> {
+    let export = WorkspaceService::export(&app)?;
+    WorkspaceService::write_file(&export, Path::new(&path))
+}
+
+#[command]
+pub async fn import_workspace(
+    app: AppHandle,
+    path: String,
+    path_remaps: Option<Vec<PathRemap>>,
+    overwrite: Option<bool>,
+) -> Result<WorkspaceImportReport, String> {
+    let export = WorkspaceService::read_file(Path::new(&path))?;
+    WorkspaceService::import(
+        &app,
+        export,
+        &path_remaps.unwrap_or_default(),
+        overwrite.unwrap_or(false),
+    )
+}
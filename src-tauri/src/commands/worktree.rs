@@ -1,16 +1,39 @@
-use crate::models::{Worktree, WorktreeStatus};
-use crate::services::GitService;
-use chrono::Utc;
+use crate::commands::github::with_github_client;
+use crate::models::{
+    CleanupCandidate, CleanupOptions, CleanupReason, CleanupReport, DiffOptions, DiffScope,
+    PullRequestState, RepositoryConfig, StructuredDiff, SyncResult, SyncStatus, SyncStrategy,
+    Worktree, WorktreeGitStatus, WorktreeStatus, WorktreeStatusChange,
+};
+use crate::services::{
+    parse_remote_url, BootstrapOutcome, DiffParser, GitCancellation, GitCredentials, GitError,
+    GitService, NamingContext, RepositoryConfigLoader, RepositoryStore, SessionStore,
+    SettingsStore, SetupApprovalStore, StoredAccount, TokenRefresher, WorktreeBootstrap,
+    WorktreeNaming, WorktreeStore, WorktreeWatcher, DEFAULT_BRANCH_TEMPLATE,
+    DEFAULT_DIRECTORY_TEMPLATE, DEFAULT_REMOTE,
+};
+use chrono::{DateTime, Duration, Utc};
 use sha2::{Digest, Sha256};
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
-use tauri::command;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{command, AppHandle, State};
 
+// The watch owner for the frontend; agent sessions own watches under their session id.
+const CLIENT_WATCH_OWNER: &str = "client";
+
+#[allow(clippy::too_many_arguments)]
 #[command]
 pub async fn create_worktree(
+    app: AppHandle,
     repo_path: String,
     issue_number: i32,
-    branch_name: String,
+    issue_title: Option<String>,
+    branch_name: Option<String>,
+    custom_branch: Option<bool>,
+    base_ref: Option<String>,
+    fetch: Option<bool>,
+    operation_id: Option<String>,
 ) -> Result<Worktree, String> {
     let repo_path = Path::new(&repo_path);
 
@@ -18,39 +41,114 @@ pub async fn create_worktree(
         return Err(format!("Repository path does not exist: {:?}", repo_path));
     }
 
-    let repo_name = repo_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| "Invalid repository path".to_string())?;
-
-    let worktrees_dir = repo_path
-        .parent()
-        .ok_or_else(|| "Cannot determine parent directory".to_string())?
-        .join(format!("{}-worktrees", repo_name));
+    let settings = SettingsStore::load(&app)?;
+    let config = RepositoryConfigLoader::load(&app, repo_path)?;
+    let worktrees_dir = SettingsStore::worktrees_dir(&settings, repo_path)?;
 
     if !worktrees_dir.exists() {
         fs::create_dir_all(&worktrees_dir)
             .map_err(|e| format!("Failed to create worktrees directory: {}", e))?;
     }
 
-    let worktree_dir_name = format!(
-        "issue-{}-{}",
-        issue_number,
-        sanitize_branch_name(&branch_name)
-    );
-    let worktree_path = worktrees_dir.join(&worktree_dir_name);
-
-    if worktree_path.exists() {
-        return Err(format!("Worktree already exists at {:?}", worktree_path));
-    }
+    let account = repository_account(&app, repo_path)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load the repository's account: {}", e);
+            None
+        });
+    let user = account.as_ref().map(|account| account.user.login.clone());
+    let title = issue_title
+        .as_deref()
+        .or(branch_name.as_deref())
+        .unwrap_or_default();
+    let context = NamingContext {
+        number: issue_number,
+        title,
+        user: user.as_deref(),
+    };
+    // The caller's branch name only seeds the slug unless it explicitly asks to use it.
+    let custom_branch = branch_name
+        .as_deref()
+        .filter(|_| custom_branch.unwrap_or(false));
+    let names = WorktreeNaming::resolve(
+        repo_path,
+        &worktrees_dir,
+        custom_branch,
+        config
+            .worktree
+            .branch_template
+            .as_deref()
+            .unwrap_or(DEFAULT_BRANCH_TEMPLATE),
+        config
+            .worktree
+            .directory_template
+            .as_deref()
+            .unwrap_or(DEFAULT_DIRECTORY_TEMPLATE),
+        &context,
+    )
+    .await?;
+    let (branch_name, worktree_path, branch_exists) =
+        (names.branch_name, names.path, names.branch_exists);
+
+    let mut worktree_started = false;
+    let base_ref = GitCancellation::scope(operation_id, async {
+        if branch_exists {
+            worktree_started = true;
+            GitService::worktree_add_existing_branch(repo_path, &worktree_path, &branch_name)
+                .await?;
+            return Ok(None);
+        }
 
-    let branch_exists = GitService::branch_exists(repo_path, &branch_name)?;
+        let base = match base_ref.or(config.worktree.base_branch.clone()) {
+            Some(base) => remote_branch_name(&base).to_string(),
+            None => GitService::get_default_branch(repo_path).await?,
+        };
+
+        // Offline, without the remote or with an expired token the worktree still gets
+        // created from whatever remote-tracking or local ref is already there.
+        if fetch.unwrap_or(true) {
+            let credentials = account
+                .map(|account| GitCredentials::new(account.auth.access_token))
+                .transpose()?;
+            match GitService::fetch(repo_path, DEFAULT_REMOTE, &base, credentials.as_ref()).await {
+                Err(e @ GitError::Cancelled { .. }) => return Err(e.into()),
+                Err(e) => eprintln!(
+                    "Failed to fetch {} from {}, using the existing ref: {}",
+                    base, DEFAULT_REMOTE, e
+                ),
+                Ok(()) => {}
+            }
+        }
 
-    if branch_exists {
-        GitService::worktree_add_existing_branch(repo_path, &worktree_path, &branch_name)?;
-    } else {
-        GitService::worktree_add(repo_path, &worktree_path, &branch_name)?;
-    }
+        // Branch from the remote-tracking ref so a stale local base branch does not leak
+        // into the new worktree; repositories without the remote branch use the local one.
+        let start_point =
+            if GitService::remote_branch_exists(repo_path, DEFAULT_REMOTE, &base).await? {
+                format!("{}/{}", DEFAULT_REMOTE, base)
+            } else {
+                base
+            };
+        let track = start_point.starts_with(&format!("{}/", DEFAULT_REMOTE));
+        worktree_started = true;
+        GitService::worktree_add(repo_path, &worktree_path, &branch_name, &start_point, track)
+            .await?;
+        Ok::<_, String>(Some(start_point))
+    })
+    .await;
+
+    // The directory (and the branch, unless it was an existing one being checked out) was
+    // free, so whatever exists under it now is the remains of a failed, cancelled or
+    // timed-out `worktree add`. This runs outside the cancelled scope.
+    let base_ref = match base_ref {
+        Ok(base_ref) => base_ref,
+        Err(e) => {
+            if worktree_started {
+                let created_branch = Some(branch_name.as_str()).filter(|_| !branch_exists);
+                discard_worktree(repo_path, &worktree_path, created_branch).await;
+            }
+            return Err(e);
+        }
+    };
 
     let worktree_path_str = worktree_path.to_string_lossy().to_string();
     let now = Utc::now().to_rfc3339();
@@ -60,99 +158,277 @@ pub async fn create_worktree(
         issue_number: Some(issue_number),
         branch_name,
         path: worktree_path_str,
-        status: WorktreeStatus::Ready,
+        status: WorktreeStatus::Creating,
+        base_ref,
+        pull_request_number: None,
+        pull_request_url: None,
+        agent_session_ids: Vec::new(),
         created_at: now.clone(),
         updated_at: now,
     };
+    WorktreeStore::save(&app, &worktree)?;
+
+    spawn_bootstrap(app, repo_path.to_path_buf(), worktree.clone(), config);
 
     Ok(worktree)
 }
 
 #[command]
-pub async fn list_worktrees(repo_path: String) -> Result<Vec<Worktree>, String> {
+pub async fn retry_worktree_setup(app: AppHandle, worktree_path: String) -> Result<(), String> {
+    let worktree = WorktreeStore::find_by_path(&app, &worktree_path)?
+        .ok_or_else(|| format!("Unknown worktree: {}", worktree_path))?;
+
+    if worktree.status != WorktreeStatus::Creating {
+        return Err(format!("Worktree is not awaiting setup: {}", worktree_path));
+    }
+
+    let repo_path = PathBuf::from(&worktree.repository_id);
+    let config = RepositoryConfigLoader::load(&app, &repo_path)?;
+    spawn_bootstrap(app, repo_path, worktree, config);
+
+    Ok(())
+}
+
+// Approves the setup commands the worktree's bootstrap is waiting on for its repository,
+// then resumes the bootstrap.
+#[command]
+pub async fn approve_worktree_setup(app: AppHandle, worktree_path: String) -> Result<(), String> {
+    let worktree = WorktreeStore::find_by_path(&app, &worktree_path)?
+        .ok_or_else(|| format!("Unknown worktree: {}", worktree_path))?;
+
+    if worktree.status != WorktreeStatus::Creating {
+        return Err(format!("Worktree is not awaiting setup: {}", worktree_path));
+    }
+
+    let repo_path = PathBuf::from(&worktree.repository_id);
+    let config = RepositoryConfigLoader::load(&app, &repo_path)?;
+    let commands = WorktreeBootstrap::setup_commands(Path::new(&worktree.path), &config)?;
+    SetupApprovalStore::approve(&app, &worktree.repository_id, &commands)?;
+    spawn_bootstrap(app, repo_path, worktree, config);
+
+    Ok(())
+}
+
+// The worktree stays `Creating` until bootstrap succeeds; a failed setup can be retried and
+// unapproved setup commands wait for `approve_worktree_setup`.
+fn spawn_bootstrap(
+    app: AppHandle,
+    repo_path: PathBuf,
+    worktree: Worktree,
+    config: RepositoryConfig,
+) {
+    tauri::async_runtime::spawn(async move {
+        let bootstrap_app = app.clone();
+        let worktree_path = PathBuf::from(&worktree.path);
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            WorktreeBootstrap::run(&bootstrap_app, &repo_path, &worktree_path, &config)
+        })
+        .await
+        .map_err(|e| format!("Bootstrap task failed: {}", e))
+        .and_then(|result| result);
+
+        match result {
+            Ok(BootstrapOutcome::Completed) => {
+                if let Err(e) =
+                    WorktreeStore::update_status(&app, &worktree.id, &WorktreeStatus::Ready)
+                {
+                    eprintln!("Failed to mark worktree ready: {}", e);
+                }
+            }
+            Ok(BootstrapOutcome::AwaitingApproval) => {}
+            Err(e) => eprintln!("Bootstrap failed for {}: {}", worktree.path, e),
+        }
+    });
+}
+
+#[command]
+pub async fn list_worktrees(app: AppHandle, repo_path: String) -> Result<Vec<Worktree>, String> {
     let repo_path = Path::new(&repo_path);
 
     if !repo_path.exists() {
         return Err(format!("Repository path does not exist: {:?}", repo_path));
     }
 
-    let worktree_infos = GitService::worktree_list_detailed(repo_path)?;
+    let worktree_infos = GitService::worktree_list_detailed(repo_path).await?;
+
+    let settings = SettingsStore::load(&app)?;
+    let worktrees_dir = SettingsStore::worktrees_dir(&settings, repo_path).ok();
+    let repository_id = repo_path.to_string_lossy().to_string();
+
+    let worktrees = WorktreeStore::reconcile(
+        &app,
+        &repository_id,
+        &worktree_infos,
+        worktrees_dir.as_deref(),
+        |info, created_at| Worktree {
+            id: generate_worktree_id(&info.path),
+            repository_id: repository_id.clone(),
+            issue_number: extract_issue_number(&info.path),
+            branch_name: info.branch.clone().unwrap_or_default(),
+            path: info.path.clone(),
+            status: WorktreeStatus::Ready,
+            base_ref: None,
+            pull_request_number: None,
+            pull_request_url: None,
+            agent_session_ids: Vec::new(),
+            created_at: created_at.clone(),
+            updated_at: created_at,
+        },
+    )?;
+
+    worktrees
+        .into_iter()
+        .map(|worktree| with_agent_sessions(&app, worktree))
+        .collect()
+}
+
+#[command]
+pub async fn update_worktree_status(
+    app: AppHandle,
+    worktree_path: String,
+    status: WorktreeStatus,
+) -> Result<Worktree, String> {
+    let worktree = WorktreeStore::find_by_path(&app, &worktree_path)?
+        .ok_or_else(|| format!("Unknown worktree: {}", worktree_path))?;
 
-    let repo_name = repo_path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    WorktreeStore::update_status(&app, &worktree.id, &status)?;
 
-    let worktrees_dir = repo_path
-        .parent()
-        .map(|p| p.join(format!("{}-worktrees", repo_name)));
+    let worktree = WorktreeStore::find_by_path(&app, &worktree_path)?
+        .ok_or_else(|| format!("Unknown worktree: {}", worktree_path))?;
+    with_agent_sessions(&app, worktree)
+}
 
-    let now = Utc::now().to_rfc3339();
+#[command]
+pub async fn get_worktree_history(
+    app: AppHandle,
+    worktree_path: String,
+) -> Result<Vec<WorktreeStatusChange>, String> {
+    let worktree = WorktreeStore::find_by_path(&app, &worktree_path)?
+        .ok_or_else(|| format!("Unknown worktree: {}", worktree_path))?;
+
+    WorktreeStore::history(&app, &worktree.id)
+}
 
-    let worktrees: Vec<Worktree> = worktree_infos
+fn with_agent_sessions(app: &AppHandle, mut worktree: Worktree) -> Result<Worktree, String> {
+    worktree.agent_session_ids = SessionStore::list_for_worktree(app, &worktree.path)?
         .into_iter()
-        .filter(|info| !info.is_bare)
-        .filter(|info| {
-            if let Some(ref dir) = worktrees_dir {
-                Path::new(&info.path).starts_with(dir)
-            } else {
-                false
-            }
-        })
-        .map(|info| {
-            let issue_number = extract_issue_number(&info.path);
-            Worktree {
-                id: generate_worktree_id(&info.path),
-                repository_id: repo_path.to_string_lossy().to_string(),
-                issue_number,
-                branch_name: info.branch.unwrap_or_default(),
-                path: info.path,
-                status: WorktreeStatus::Ready,
-                created_at: now.clone(),
-                updated_at: now.clone(),
-            }
-        })
+        .map(|session| session.id)
         .collect();
-
-    Ok(worktrees)
+    Ok(worktree)
 }
 
 #[command]
-pub async fn remove_worktree(worktree_path: String, force: bool) -> Result<(), String> {
+pub async fn remove_worktree(
+    app: AppHandle,
+    watcher: State<'_, Arc<Mutex<WorktreeWatcher>>>,
+    worktree_path: String,
+    force: bool,
+) -> Result<(), String> {
     let worktree_path = Path::new(&worktree_path);
 
     if !worktree_path.exists() {
         return Err(format!("Worktree path does not exist: {:?}", worktree_path));
     }
 
-    let repo_path = find_main_repo_from_worktree(worktree_path)?;
+    let repo_path = find_main_repo_from_worktree(worktree_path).await?;
 
-    GitService::worktree_remove(&repo_path, worktree_path, force)?;
+    let owners = stop_watching(&watcher, &worktree_path.to_string_lossy())?;
+    if let Err(e) = GitService::worktree_remove(&repo_path, worktree_path, force).await {
+        resume_watching(&watcher, &app, &worktree_path.to_string_lossy(), owners);
+        return Err(e.into());
+    }
+
+    if let Some(worktree) = WorktreeStore::find_by_path(&app, &worktree_path.to_string_lossy())? {
+        WorktreeStore::update_status(&app, &worktree.id, &WorktreeStatus::Removed)?;
+    }
 
     Ok(())
 }
 
-fn find_main_repo_from_worktree(worktree_path: &Path) -> Result<std::path::PathBuf, String> {
-    let worktrees_dir = worktree_path
-        .parent()
-        .ok_or_else(|| "Cannot determine parent directory of worktree".to_string())?;
+async fn discard_worktree(repo_path: &Path, worktree_path: &Path, branch_name: Option<&str>) {
+    if let Err(e) = GitService::worktree_remove(repo_path, worktree_path, true).await {
+        eprintln!("Failed to remove worktree {:?}: {}", worktree_path, e);
+    }
+    if worktree_path.exists() {
+        if let Err(e) = fs::remove_dir_all(worktree_path) {
+            eprintln!("Failed to remove {:?}: {}", worktree_path, e);
+        }
+    }
+    if let Err(e) = GitService::worktree_prune(repo_path).await {
+        eprintln!("Failed to prune worktrees: {}", e);
+    }
+    // A branch that existed before is left alone: it may hold work of its own.
+    let Some(branch_name) = branch_name else {
+        return;
+    };
+    if GitService::branch_exists(repo_path, branch_name)
+        .await
+        .unwrap_or(false)
+    {
+        if let Err(e) = GitService::delete_branch(repo_path, branch_name, true).await {
+            eprintln!("Failed to delete branch {}: {}", branch_name, e);
+        }
+    }
+}
 
-    let worktrees_dir_name = worktrees_dir
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| "Invalid worktrees directory name".to_string())?;
+// A watched directory cannot be deleted on Windows, and elsewhere the removal would only
+// be reported as a flood of deletions. Returns the watch owners for `resume_watching`.
+fn stop_watching(
+    watcher: &Mutex<WorktreeWatcher>,
+    worktree_path: &str,
+) -> Result<Vec<String>, String> {
+    Ok(watcher
+        .lock()
+        .map_err(|e| e.to_string())?
+        .stop(worktree_path))
+}
 
-    let repo_name = worktrees_dir_name
-        .strip_suffix("-worktrees")
-        .ok_or_else(|| {
-            format!(
-                "Worktrees directory does not follow expected naming convention: {}",
-                worktrees_dir_name
-            )
-        })?;
+// Restores the watch after a failed removal.
+fn resume_watching(
+    watcher: &Mutex<WorktreeWatcher>,
+    app: &AppHandle,
+    worktree_path: &str,
+    owners: Vec<String>,
+) {
+    let Ok(mut watcher) = watcher.lock() else {
+        return;
+    };
+    for owner in owners {
+        if let Err(e) = watcher.watch(app.clone(), worktree_path, &owner) {
+            eprintln!("Failed to watch worktree {}: {}", worktree_path, e);
+            return;
+        }
+    }
+}
+
+#[command]
+pub async fn watch_worktree(
+    state: State<'_, Arc<Mutex<WorktreeWatcher>>>,
+    app_handle: AppHandle,
+    worktree_path: String,
+) -> Result<(), String> {
+    let mut watcher = state.lock().map_err(|e| e.to_string())?;
+    watcher.watch(app_handle, &worktree_path, CLIENT_WATCH_OWNER)
+}
+
+#[command]
+pub async fn unwatch_worktree(
+    state: State<'_, Arc<Mutex<WorktreeWatcher>>>,
+    worktree_path: String,
+) -> Result<(), String> {
+    let mut watcher = state.lock().map_err(|e| e.to_string())?;
+    watcher.unwatch(&worktree_path, CLIENT_WATCH_OWNER);
+    Ok(())
+}
 
-    let parent_dir = worktrees_dir
-        .parent()
-        .ok_or_else(|| "Cannot determine parent directory of worktrees directory".to_string())?;
+pub(crate) async fn find_main_repo_from_worktree(worktree_path: &Path) -> Result<PathBuf, String> {
+    let main = GitService::worktree_list_detailed(worktree_path)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("No worktrees found for {:?}", worktree_path))?;
 
-    let repo_path = parent_dir.join(repo_name);
+    let repo_path = PathBuf::from(main.path);
 
     if !repo_path.exists() {
         return Err(format!("Main repository not found at {:?}", repo_path));
@@ -162,29 +438,523 @@ fn find_main_repo_from_worktree(worktree_path: &Path) -> Result<std::path::PathB
 }
 
 #[command]
-pub async fn get_worktree_diff(worktree_path: String) -> Result<String, String> {
-    let worktree_path = Path::new(&worktree_path);
+pub async fn get_worktree_diff(
+    app: AppHandle,
+    worktree_path: String,
+    scope: Option<DiffScope>,
+    paths: Option<Vec<String>>,
+) -> Result<String, String> {
+    let worktree_path = PathBuf::from(&worktree_path);
 
     if !worktree_path.exists() {
         return Err(format!("Worktree path does not exist: {:?}", worktree_path));
     }
 
-    let default_branch = GitService::get_default_branch(worktree_path)?;
+    let base_branch = resolve_base_ref(&app, &worktree_path).await?;
+    let scope = scope.unwrap_or_default();
+    let paths = paths.unwrap_or_default();
+
+    let args = GitService::diff_scope_args(&worktree_path, &scope, &base_branch, &paths).await?;
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let mut patch = GitService::diff_patch(&worktree_path, &args).await?;
+    if scope.includes_working_tree() {
+        patch.push_str(&GitService::untracked_patch(&worktree_path, &paths, None).await?);
+    }
+    Ok(patch)
+}
+
+#[command]
+pub async fn get_worktree_diff_files(
+    app: AppHandle,
+    worktree_path: String,
+    options: Option<DiffOptions>,
+    scope: Option<DiffScope>,
+    paths: Option<Vec<String>>,
+) -> Result<StructuredDiff, String> {
+    let worktree_path = PathBuf::from(&worktree_path);
+
+    if !worktree_path.exists() {
+        return Err(format!("Worktree path does not exist: {:?}", worktree_path));
+    }
+
+    let base_branch = resolve_base_ref(&app, &worktree_path).await?;
+    let options = options.unwrap_or_default();
+    let scope = scope.unwrap_or_default();
+    let paths = paths.unwrap_or_default();
+
+    let args = GitService::diff_scope_args(&worktree_path, &scope, &base_branch, &paths).await?;
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let mut diff = GitService::structured_diff(&worktree_path, &args, &options).await?;
+    if scope.includes_working_tree() {
+        let patch =
+            GitService::untracked_patch(&worktree_path, &paths, options.context_lines).await?;
+        let untracked = DiffParser::parse(&patch);
+        diff.stats.files_changed += untracked.stats.files_changed;
+        diff.stats.additions += untracked.stats.additions;
+        diff.files.extend(untracked.files);
+    }
+    Ok(diff)
+}
+
+#[command]
+pub async fn get_worktree_status(
+    app: AppHandle,
+    worktree_path: String,
+) -> Result<WorktreeGitStatus, String> {
+    let worktree_path = PathBuf::from(&worktree_path);
+
+    if !worktree_path.exists() {
+        return Err(format!("Worktree path does not exist: {:?}", worktree_path));
+    }
+
+    let base_ref = resolve_base_ref(&app, &worktree_path).await.ok();
+
+    Ok(GitService::status(&worktree_path, base_ref.as_deref()).await?)
+}
 
-    GitService::diff_with_base(worktree_path, &default_branch)
+#[command]
+pub async fn get_repository_worktree_statuses(
+    app: AppHandle,
+    repo_path: String,
+) -> Result<Vec<WorktreeGitStatus>, String> {
+    let paths: Vec<PathBuf> = WorktreeStore::list_for_repository(&app, &repo_path)?
+        .into_iter()
+        .map(|worktree| PathBuf::from(worktree.path))
+        .filter(|path| path.exists())
+        .collect();
+
+    let mut statuses = Vec::new();
+    for path in paths {
+        let base_ref = resolve_base_ref(&app, &path).await.ok();
+        statuses.push(GitService::status(&path, base_ref.as_deref()).await?);
+    }
+
+    Ok(statuses)
 }
 
-fn sanitize_branch_name(name: &str) -> String {
-    name.chars()
-        .map(|c| {
-            if c.is_alphanumeric() || c == '-' || c == '_' {
-                c
+#[command]
+pub async fn sync_worktree(
+    app: AppHandle,
+    worktree_path: String,
+    strategy: Option<SyncStrategy>,
+    fetch: Option<bool>,
+    operation_id: Option<String>,
+) -> Result<SyncResult, String> {
+    let worktree_path = PathBuf::from(&worktree_path);
+
+    if !worktree_path.exists() {
+        return Err(format!("Worktree path does not exist: {:?}", worktree_path));
+    }
+
+    let status = GitService::status(&worktree_path, None).await?;
+    if let Some(operation) = status.operation {
+        return Err(format!(
+            "A {:?} is already in progress; continue or abort it first",
+            operation
+        ));
+    }
+    if status.staged + status.unstaged > 0 || !status.conflicted.is_empty() {
+        return Err("Worktree has uncommitted changes; commit or stash them first".to_string());
+    }
+
+    let base = resolve_base_ref(&app, &worktree_path).await?;
+    let branch = remote_branch_name(&base).to_string();
+
+    GitCancellation::scope(operation_id, async {
+        if fetch.unwrap_or(true) {
+            let repo_path = find_main_repo_from_worktree(&worktree_path).await?;
+            let credentials = repository_account(&app, &repo_path)
+                .await?
+                .map(|account| GitCredentials::new(account.auth.access_token))
+                .transpose()?;
+            match GitService::fetch(&repo_path, DEFAULT_REMOTE, &branch, credentials.as_ref()).await
+            {
+                Err(e @ GitError::Cancelled { .. }) => return Err(e.into()),
+                Err(e) => eprintln!(
+                    "Failed to fetch {} from {}, syncing with the existing ref: {}",
+                    branch, DEFAULT_REMOTE, e
+                ),
+                Ok(()) => {}
+            }
+        }
+
+        // A base recorded as a local branch (older worktrees, configured or default
+        // branches) is synced against its remote-tracking branch, which the fetch updated.
+        let base_ref =
+            if GitService::remote_branch_exists(&worktree_path, DEFAULT_REMOTE, &branch).await? {
+                format!("{}/{}", DEFAULT_REMOTE, branch)
             } else {
-                '-'
+                base
+            };
+
+        if GitService::is_ancestor(&worktree_path, &base_ref, "HEAD").await? {
+            return Ok(SyncResult {
+                status: SyncStatus::UpToDate,
+                base_ref: base_ref.clone(),
+                operation: None,
+                conflicts: vec![],
+            });
+        }
+
+        let strategy = strategy.unwrap_or_default();
+        let clean = GitService::sync(&worktree_path, &base_ref, strategy).await?;
+
+        sync_result(&worktree_path, base_ref.clone(), clean).await
+    })
+    .await
+}
+
+#[command]
+pub async fn continue_worktree_sync(
+    app: AppHandle,
+    worktree_path: String,
+) -> Result<SyncResult, String> {
+    let worktree_path = PathBuf::from(&worktree_path);
+    let operation = GitService::operation_in_progress(&worktree_path)
+        .await?
+        .ok_or_else(|| "No rebase or merge is in progress".to_string())?;
+
+    let conflicts = GitService::conflicts(&worktree_path).await?;
+    if !conflicts.is_empty() {
+        return Ok(SyncResult {
+            status: SyncStatus::Conflicts,
+            base_ref: resolve_base_ref(&app, &worktree_path).await?,
+            operation: Some(operation),
+            conflicts,
+        });
+    }
+
+    let clean = GitService::continue_operation(&worktree_path, &operation).await?;
+
+    sync_result(
+        &worktree_path,
+        resolve_base_ref(&app, &worktree_path).await?,
+        clean,
+    )
+    .await
+}
+
+#[command]
+pub async fn abort_worktree_sync(worktree_path: String) -> Result<(), String> {
+    let worktree_path = Path::new(&worktree_path);
+    let operation = GitService::operation_in_progress(worktree_path)
+        .await?
+        .ok_or_else(|| "No rebase or merge is in progress".to_string())?;
+
+    Ok(GitService::abort_operation(worktree_path, &operation).await?)
+}
+
+// A rebase that finished one conflicting commit may stop again on the next one, so the
+// result is always read back from the worktree.
+async fn sync_result(
+    worktree_path: &Path,
+    base_ref: String,
+    clean: bool,
+) -> Result<SyncResult, String> {
+    if clean {
+        return Ok(SyncResult {
+            status: SyncStatus::Synced,
+            base_ref,
+            operation: None,
+            conflicts: vec![],
+        });
+    }
+
+    Ok(SyncResult {
+        status: SyncStatus::Conflicts,
+        base_ref,
+        operation: GitService::operation_in_progress(worktree_path).await?,
+        conflicts: GitService::conflicts(worktree_path).await?,
+    })
+}
+
+#[command]
+pub async fn cleanup_worktrees(
+    app: AppHandle,
+    watcher: State<'_, Arc<Mutex<WorktreeWatcher>>>,
+    repo_path: String,
+    options: Option<CleanupOptions>,
+    dry_run: bool,
+    worktree_paths: Option<Vec<String>>,
+) -> Result<CleanupReport, String> {
+    let options = options.unwrap_or_default();
+    let repo_path = Path::new(&repo_path);
+
+    if !repo_path.exists() {
+        return Err(format!("Repository path does not exist: {:?}", repo_path));
+    }
+
+    let repository = RepositoryStore::load_all(&app)?
+        .into_iter()
+        .find(|repository| Path::new(&repository.local_path) == repo_path);
+    let stale_cutoff = options
+        .stale_days
+        .map(|days| Utc::now() - Duration::days(i64::from(days)));
+
+    let mut report = CleanupReport {
+        dry_run,
+        ..Default::default()
+    };
+    // Branches whose every commit is known to have landed, in the base or in the merged
+    // pull request, and so can be force-deleted.
+    let mut landed_branches = HashSet::new();
+
+    for worktree in WorktreeStore::list_for_repository(&app, &repo_path.to_string_lossy())? {
+        let path = Path::new(&worktree.path);
+        let selected = worktree_paths
+            .as_ref()
+            .is_none_or(|paths| paths.contains(&worktree.path));
+        if !selected || worktree.status == WorktreeStatus::Creating || !path.exists() {
+            continue;
+        }
+
+        let base_ref = resolve_base_ref(&app, path).await.ok();
+        let base_branch = base_ref.as_deref().map(|base| {
+            base.strip_prefix(&format!("{}/", DEFAULT_REMOTE))
+                .unwrap_or(base)
+        });
+        if worktree.branch_name.is_empty() || base_branch == Some(worktree.branch_name.as_str()) {
+            continue;
+        }
+
+        let mut reasons = Vec::new();
+        let last_commit_at = GitService::last_commit_date(repo_path, &worktree.branch_name)
+            .await
+            .ok()
+            .flatten();
+
+        // A branch without commits of its own is trivially contained in its base, so it only
+        // counts as merged when its tip was committed after the worktree was created.
+        if let Some(ref base) = base_ref {
+            let has_own_commits = last_commit_at
+                .as_deref()
+                .is_some_and(|date| parse_time(date) > parse_time(&worktree.created_at));
+            if has_own_commits
+                && GitService::is_ancestor(repo_path, &worktree.branch_name, base)
+                    .await
+                    .unwrap_or(false)
+            {
+                reasons.push(CleanupReason::BranchMerged);
+                landed_branches.insert(worktree.branch_name.clone());
             }
-        })
-        .collect::<String>()
-        .to_lowercase()
+        }
+
+        if let (Some(number), Some(repository)) = (worktree.pull_request_number, &repository) {
+            let (owner, name) = (&repository.owner, &repository.name);
+            let result = with_github_client(
+                &app,
+                repository.account_id.as_deref(),
+                |client| async move { client.get_pull_request(owner, name, number).await },
+            )
+            .await;
+            match result {
+                Ok(pull_request) if pull_request.merged_at.is_some() => {
+                    reasons.push(CleanupReason::PullRequestMerged);
+                    // Commits made after the merge, or never pushed, are not in its head.
+                    if GitService::is_ancestor(
+                        repo_path,
+                        &worktree.branch_name,
+                        &pull_request.head.sha,
+                    )
+                    .await
+                    .unwrap_or(false)
+                    {
+                        landed_branches.insert(worktree.branch_name.clone());
+                    }
+                }
+                Ok(pull_request) if matches!(pull_request.state, PullRequestState::Closed) => {
+                    reasons.push(CleanupReason::PullRequestClosed)
+                }
+                Ok(_) => {}
+                Err(e) => report.errors.push(format!(
+                    "Failed to check pull request #{} for {}: {}",
+                    number, worktree.path, e
+                )),
+            }
+        }
+
+        if let Some(cutoff) = stale_cutoff {
+            let last_activity = [
+                last_commit_at.as_deref(),
+                Some(worktree.updated_at.as_str()),
+            ]
+            .into_iter()
+            .flatten()
+            .filter_map(parse_time)
+            .max();
+            if last_activity.is_some_and(|time| time < cutoff) {
+                reasons.push(CleanupReason::Stale);
+            }
+        }
+
+        if reasons.is_empty() {
+            continue;
+        }
+
+        let has_local_changes = GitService::status(path, None)
+            .await
+            .map(|status| {
+                status.staged + status.unstaged + status.untracked > 0
+                    || !status.conflicted.is_empty()
+            })
+            .unwrap_or(true);
+
+        report.candidates.push(CleanupCandidate {
+            worktree_path: worktree.path.clone(),
+            branch_name: worktree.branch_name.clone(),
+            will_remove: !has_local_changes || options.force,
+            reasons,
+            last_commit_at,
+            has_local_changes,
+        });
+    }
+
+    if dry_run {
+        return Ok(report);
+    }
+
+    let credentials = if options.delete_remote_branches {
+        repository_account(&app, repo_path)
+            .await?
+            .map(|account| GitCredentials::new(account.auth.access_token))
+            .transpose()?
+    } else {
+        None
+    };
+
+    for candidate in report.candidates.iter().filter(|c| c.will_remove) {
+        let path = Path::new(&candidate.worktree_path);
+        let owners = stop_watching(&watcher, &candidate.worktree_path)?;
+        if let Err(e) = GitService::worktree_remove(repo_path, path, options.force).await {
+            resume_watching(&watcher, &app, &candidate.worktree_path, owners);
+            report.errors.push(format!(
+                "Failed to remove {}: {}",
+                candidate.worktree_path, e
+            ));
+            continue;
+        }
+        if let Some(worktree) = WorktreeStore::find_by_path(&app, &candidate.worktree_path)? {
+            WorktreeStore::update_status(&app, &worktree.id, &WorktreeStatus::Removed)?;
+        }
+        report
+            .removed_worktrees
+            .push(candidate.worktree_path.clone());
+
+        // Squash merges leave the branch unmerged as far as git can tell, so branches whose
+        // commits all landed are force-deleted; any other branch must be fully merged.
+        let force_delete = options.force || landed_branches.contains(&candidate.branch_name);
+        match GitService::delete_branch(repo_path, &candidate.branch_name, force_delete).await {
+            Ok(()) => report.deleted_branches.push(candidate.branch_name.clone()),
+            Err(e) => report.errors.push(format!(
+                "Failed to delete branch {}: {}",
+                candidate.branch_name, e
+            )),
+        }
+
+        if options.delete_remote_branches {
+            match GitService::delete_remote_branch(
+                repo_path,
+                DEFAULT_REMOTE,
+                &candidate.branch_name,
+                credentials.as_ref(),
+            )
+            .await
+            {
+                Ok(()) => report
+                    .deleted_remote_branches
+                    .push(candidate.branch_name.clone()),
+                Err(e) => report.errors.push(format!(
+                    "Failed to delete remote branch {}: {}",
+                    candidate.branch_name, e
+                )),
+            }
+        }
+    }
+
+    GitService::worktree_prune(repo_path).await?;
+
+    Ok(report)
+}
+
+// The branch name behind a base given as "origin/main" or "refs/remotes/origin/main", so it
+// is not qualified with the remote a second time.
+fn remote_branch_name(base: &str) -> &str {
+    let remote_prefix = format!("{}/", DEFAULT_REMOTE);
+    let base = base.strip_prefix("refs/remotes/").unwrap_or(base);
+    base.strip_prefix(&remote_prefix)
+        .or_else(|| base.strip_prefix("refs/heads/"))
+        .unwrap_or(base)
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+// The base a worktree is compared against: the ref it was created from, then the
+// repository's configured base branch, then the remote default branch.
+pub(crate) async fn resolve_base_ref(
+    app: &AppHandle,
+    worktree_path: &Path,
+) -> Result<String, String> {
+    let recorded = WorktreeStore::find_by_path(app, &worktree_path.to_string_lossy())?
+        .and_then(|worktree| worktree.base_ref);
+    if let Some(base_ref) = recorded {
+        return Ok(base_ref);
+    }
+
+    let config = RepositoryConfigLoader::load(app, worktree_path)?;
+    match config.worktree.base_branch {
+        Some(branch) => Ok(branch),
+        None => Ok(GitService::get_default_branch(worktree_path).await?),
+    }
+}
+
+// The account the repository is registered with. Only repositories registered without
+// one fall back to the active account, and a token is only handed out for the host of
+// the repository's remote.
+pub(crate) async fn repository_account(
+    app: &AppHandle,
+    repo_path: &Path,
+) -> Result<Option<StoredAccount>, String> {
+    let account_id = RepositoryStore::load_all(app)?
+        .into_iter()
+        .find(|repository| Path::new(&repository.local_path) == repo_path)
+        .and_then(|repository| repository.account_id);
+
+    let account = match account_id {
+        Some(ref id) => TokenRefresher::load_fresh(app, Some(id))
+            .await
+            .map_err(|e| format!("Account {} is not signed in: {}", id, e))?
+            .ok_or_else(|| format!("Account {} is not signed in", id))?,
+        None => match TokenRefresher::load_fresh(app, None).await {
+            Ok(Some(account)) => account,
+            Ok(None) => return Ok(None),
+            Err(e) => {
+                eprintln!("Failed to load the active account: {}", e);
+                return Ok(None);
+            }
+        },
+    };
+
+    let remote_host = GitService::remotes(repo_path)
+        .await?
+        .into_iter()
+        .find(|(remote, _)| remote == DEFAULT_REMOTE)
+        .and_then(|(_, url)| parse_remote_url(&url))
+        .map(|(host, _, _)| host);
+
+    match remote_host {
+        Some(host) if host.eq_ignore_ascii_case(&account.host) => Ok(Some(account)),
+        Some(host) if account_id.is_some() => Err(format!(
+            "Account {} is for {}, but the repository's remote is on {}",
+            account.id(),
+            account.host,
+            host
+        )),
+        _ => Ok(None),
+    }
 }
 
 fn extract_issue_number(path: &str) -> Option<i32> {
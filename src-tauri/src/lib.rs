@@ -3,15 +3,26 @@ pub mod models;
 pub mod services;
 
 use commands::{
-    close_pty, create_pty_session, create_pull_request, create_worktree, delete_repository,
-    exchange_oauth_code, get_agent_status, get_authenticated_user, get_issue, get_stored_token,
-    get_worktree_diff, list_issues, list_labels, list_milestones, list_worktrees,
-    load_repositories, logout, remove_worktree, resize_pty, save_repository, send_agent_input,
-    start_agent, start_oauth_flow, stop_agent, write_pty,
+    abort_worktree_sync, add_token_account, approve_worktree_setup, cancel_git_operation,
+    cleanup_worktrees, clear_github_app_config, clone_repository, close_pty, commit_changes,
+    configure_github_app, continue_worktree_sync, create_issue_comment, create_pty_session,
+    create_pull_request, create_worktree, delete_repository, discover_repositories,
+    exchange_oauth_code, export_workspace, generate_commit_message, get_agent_status,
+    get_authenticated_user, get_board_config, get_cached_issues, get_commit_diff,
+    get_file_versions, get_github_app_config, get_issue, get_repository_config,
+    get_repository_worktree_statuses, get_settings, get_staged_changes, get_stored_token,
+    get_worktree_commits, get_worktree_diff, get_worktree_diff_files, get_worktree_history,
+    get_worktree_status, import_workspace, lint_commit_message, list_accounts, list_issues,
+    list_labels, list_milestones, list_worktrees, load_repositories, logout, push_worktree_branch,
+    register_repositories, remove_account, remove_worktree, reset_to_commit, resize_pty,
+    retry_worktree_setup, revert_commit, save_board_config, save_repository, send_agent_input,
+    stage_hunk, stage_paths, start_agent, start_oauth_flow, stop_agent, switch_account,
+    sync_worktree, unstage_hunk, unstage_paths, unwatch_worktree, update_settings,
+    update_worktree_status, watch_worktree, write_pty,
 };
-use services::{AgentManager, PtyManager};
+use services::{AgentManager, Database, PtyManager, WorktreeWatcher};
 use std::sync::{Arc, Mutex};
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 use tauri_plugin_deep_link::DeepLinkExt;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -26,7 +37,12 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .manage(Arc::new(Mutex::new(PtyManager::new())))
         .manage(Arc::new(Mutex::new(AgentManager::new())))
+        .manage(Arc::new(Mutex::new(WorktreeWatcher::new())))
         .setup(|app| {
+            let database = Database::open(app.handle())?;
+            database.import_legacy_stores(app.handle())?;
+            app.manage(database);
+
             #[cfg(desktop)]
             {
                 let handle = app.handle().clone();
@@ -47,17 +63,57 @@ pub fn run() {
             get_authenticated_user,
             logout,
             get_stored_token,
+            // Accounts
+            list_accounts,
+            switch_account,
+            remove_account,
+            add_token_account,
+            // GitHub App
+            configure_github_app,
+            get_github_app_config,
+            clear_github_app_config,
             // GitHub Issues API
             list_issues,
+            get_cached_issues,
             get_issue,
             list_labels,
             list_milestones,
             create_pull_request,
+            create_issue_comment,
             // Worktree
             create_worktree,
+            retry_worktree_setup,
+            approve_worktree_setup,
             list_worktrees,
             remove_worktree,
+            cleanup_worktrees,
+            sync_worktree,
+            continue_worktree_sync,
+            abort_worktree_sync,
             get_worktree_diff,
+            get_worktree_diff_files,
+            update_worktree_status,
+            get_worktree_history,
+            get_worktree_status,
+            get_repository_worktree_statuses,
+            watch_worktree,
+            unwatch_worktree,
+            // Git
+            stage_paths,
+            unstage_paths,
+            stage_hunk,
+            unstage_hunk,
+            get_staged_changes,
+            generate_commit_message,
+            lint_commit_message,
+            commit_changes,
+            push_worktree_branch,
+            cancel_git_operation,
+            get_file_versions,
+            get_worktree_commits,
+            get_commit_diff,
+            revert_commit,
+            reset_to_commit,
             // Agent
             start_agent,
             stop_agent,
@@ -70,8 +126,21 @@ pub fn run() {
             close_pty,
             // Storage
             save_repository,
+            clone_repository,
+            discover_repositories,
+            register_repositories,
             load_repositories,
             delete_repository,
+            get_repository_config,
+            // Settings
+            get_settings,
+            update_settings,
+            // Board
+            get_board_config,
+            save_board_config,
+            // Workspace
+            export_workspace,
+            import_workspace,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
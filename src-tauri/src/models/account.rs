@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubAccount {
+    pub id: String,
+    pub host: String,
+    pub login: String,
+    pub name: Option<String>,
+    pub avatar_url: String,
+    pub is_active: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthExpiredEvent {
+    pub account_id: String,
+    pub reason: String,
+}
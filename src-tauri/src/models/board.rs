@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use super::ListIssuesStateFilter;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KanbanColumn {
+    pub id: String,
+    pub title: String,
+    pub order: i32,
+    pub is_default: bool,
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedFilter {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub repository: Option<String>,
+    #[serde(default)]
+    pub state: Option<ListIssuesStateFilter>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub milestone: Option<String>,
+    #[serde(default)]
+    pub assignee: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardConfig {
+    #[serde(default)]
+    pub columns: Vec<KanbanColumn>,
+    #[serde(default)]
+    pub saved_filters: Vec<SavedFilter>,
+}
@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ConflictFile, GitOperation};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileChangeStatus {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Copied,
+    ModeChanged,
+    TypeChanged,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChangeSummary {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub status: FileChangeStatus,
+    pub additions: Option<u32>,
+    pub deletions: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitResult {
+    pub sha: String,
+    pub subject: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PushStatus {
+    Created,
+    Updated,
+    ForceUpdated,
+    UpToDate,
+    Rejected,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PushRejectionReason {
+    NonFastForward,
+    StaleLease,
+    ProtectedBranch,
+    PermissionDenied,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushResult {
+    pub branch: String,
+    pub remote: String,
+    pub status: PushStatus,
+    pub rejection: Option<PushRejectionReason>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushProgressEvent {
+    pub worktree_path: String,
+    pub phase: String,
+    pub percent: Option<u8>,
+    pub message: String,
+}
+
+// Which two trees a worktree diff compares. Scopes that end at the working tree also list
+// untracked (not ignored) files as added.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum DiffScope {
+    // Merge-base with the base branch against the working tree, committed or not.
+    #[default]
+    Base,
+    // HEAD against the working tree.
+    Uncommitted,
+    // HEAD against the index.
+    Staged,
+    // The index against the working tree.
+    Unstaged,
+    // Merge-base with the base branch against HEAD.
+    Branch,
+    Commit {
+        sha: String,
+    },
+    Range {
+        from: String,
+        to: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WhitespaceMode {
+    #[default]
+    Show,
+    IgnoreAtEol,
+    IgnoreChange,
+    IgnoreAll,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffOptions {
+    pub whitespace: WhitespaceMode,
+    pub detect_renames: bool,
+    pub detect_copies: bool,
+    pub rename_threshold: Option<u8>,
+    pub context_lines: Option<u32>,
+}
+
+impl DiffScope {
+    pub fn includes_working_tree(&self) -> bool {
+        matches!(
+            self,
+            DiffScope::Base | DiffScope::Uncommitted | DiffScope::Unstaged
+        )
+    }
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            whitespace: WhitespaceMode::Show,
+            detect_renames: true,
+            detect_copies: false,
+            rename_threshold: None,
+            context_lines: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffLineKind {
+    Context,
+    Addition,
+    Deletion,
+    NoNewline,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffHunk {
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub section: Option<String>,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub status: FileChangeStatus,
+    pub old_mode: Option<String>,
+    pub new_mode: Option<String>,
+    pub similarity: Option<u8>,
+    pub is_binary: bool,
+    pub additions: u32,
+    pub deletions: u32,
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiffStats {
+    pub files_changed: u32,
+    pub additions: u32,
+    pub deletions: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StructuredDiff {
+    pub files: Vec<FileDiff>,
+    pub stats: DiffStats,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileContentKind {
+    Text,
+    Image,
+    Binary,
+    TooLarge,
+}
+
+// Text is decoded to UTF-8 (`encoding` names the source encoding); images are base64.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileContent {
+    pub kind: FileContentKind,
+    pub size: u64,
+    pub encoding: Option<String>,
+    pub mime_type: Option<String>,
+    pub content: Option<String>,
+}
+
+// `None` on either side means the file does not exist there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileVersions {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub base_ref: String,
+    pub base: Option<FileContent>,
+    pub working: Option<FileContent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitInfo {
+    pub sha: String,
+    pub short_sha: String,
+    pub parents: Vec<String>,
+    pub author_name: String,
+    pub author_email: String,
+    pub authored_at: String,
+    pub subject: String,
+    pub body: String,
+    pub stats: DiffStats,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResetMode {
+    Soft,
+    Mixed,
+    Hard,
+}
+
+// `sha` is the revert commit; it is `None` while the revert is stopped on conflicts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevertResult {
+    pub sha: Option<String>,
+    pub operation: Option<GitOperation>,
+    pub conflicts: Vec<ConflictFile>,
+}
@@ -164,10 +164,27 @@ pub struct PullRequest {
     pub html_url: String,
     pub head: PullRequestRef,
     pub base: PullRequestRef,
+    #[serde(default)]
+    pub merged_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueComment {
+    pub id: i64,
+    pub body: String,
+    pub html_url: String,
+    pub user: IssueUser,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateIssueCommentRequest {
+    pub body: String,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct CreatePullRequestRequest {
     pub title: String,
@@ -1,11 +1,25 @@
+pub mod account;
 pub mod agent;
+pub mod board;
+pub mod git;
+pub mod github_app;
 pub mod issue;
 pub mod pty;
 pub mod repository;
+pub mod repository_config;
+pub mod settings;
+pub mod workspace;
 pub mod worktree;
 
+pub use account::*;
 pub use agent::*;
+pub use board::*;
+pub use git::*;
+pub use github_app::*;
 pub use issue::*;
 pub use pty::*;
 pub use repository::*;
+pub use repository_config::*;
+pub use settings::*;
+pub use workspace::*;
 pub use worktree::*;
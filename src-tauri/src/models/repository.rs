@@ -9,6 +9,8 @@ pub struct Repository {
     pub local_path: String,
     pub default_branch: String,
     pub is_private: bool,
+    #[serde(default)]
+    pub account_id: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -19,3 +21,35 @@ pub struct RepositoryFormData {
     pub name: String,
     pub local_path: String,
 }
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CloneOptions {
+    #[serde(default)]
+    pub depth: Option<u32>,
+    #[serde(default)]
+    pub filter: Option<String>,
+    #[serde(default)]
+    pub single_branch: bool,
+    #[serde(default)]
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloneProgressEvent {
+    pub full_name: String,
+    pub phase: String,
+    pub percent: Option<u8>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredRepository {
+    pub local_path: String,
+    pub host: String,
+    pub owner: String,
+    pub name: String,
+    pub full_name: String,
+    pub remote_name: String,
+    pub default_branch: Option<String>,
+    pub registered_id: Option<String>,
+}
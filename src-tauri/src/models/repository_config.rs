@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RepositoryConfig {
+    pub worktree: WorktreeConfig,
+    pub agent: AgentConfig,
+    pub commit: CommitConfig,
+    pub env: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorktreeConfig {
+    pub base_branch: Option<String>,
+    pub branch_template: Option<String>,
+    pub directory_template: Option<String>,
+    pub copy_files: Vec<String>,
+    pub symlink_files: Vec<String>,
+    pub setup: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AgentConfig {
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CommitConfig {
+    pub max_subject_length: usize,
+    pub conventional: bool,
+    pub types: Vec<String>,
+    pub require_issue_reference: bool,
+}
+
+impl Default for CommitConfig {
+    fn default() -> Self {
+        Self {
+            max_subject_length: 72,
+            conventional: false,
+            types: [
+                "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore",
+                "revert",
+            ]
+            .iter()
+            .map(|t| t.to_string())
+            .collect(),
+            require_issue_reference: false,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use super::{BoardConfig, Settings};
+
+pub const WORKSPACE_FORMAT_VERSION: u32 = 1;
+pub const HOME_PLACEHOLDER: &str = "${HOME}";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceRepository {
+    pub id: String,
+    pub owner: String,
+    pub name: String,
+    pub full_name: String,
+    pub local_path: String,
+    pub default_branch: String,
+    pub is_private: bool,
+    // Only kept on import when the account is signed in on the importing machine.
+    #[serde(default)]
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceExport {
+    pub version: u32,
+    pub exported_at: String,
+    pub repositories: Vec<WorkspaceRepository>,
+    pub settings: Settings,
+    #[serde(default)]
+    pub board: BoardConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathRemap {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceConflictKind {
+    RepositoryExists,
+    MissingPath,
+    MissingAccount,
+    SettingsExist,
+    InvalidSettings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceConflict {
+    pub kind: WorkspaceConflictKind,
+    pub subject: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceImportReport {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub skipped: Vec<String>,
+    pub conflicts: Vec<WorkspaceConflict>,
+    pub settings_applied: bool,
+    pub board_applied: bool,
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum WorktreeStatus {
     Creating,
@@ -8,6 +8,7 @@ pub enum WorktreeStatus {
     Working,
     Reviewing,
     Merged,
+    Removed,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +19,160 @@ pub struct Worktree {
     pub branch_name: String,
     pub path: String,
     pub status: WorktreeStatus,
+    #[serde(default)]
+    pub base_ref: Option<String>,
+    #[serde(default)]
+    pub pull_request_number: Option<i32>,
+    #[serde(default)]
+    pub pull_request_url: Option<String>,
+    #[serde(default)]
+    pub agent_session_ids: Vec<String>,
     pub created_at: String,
     pub updated_at: String,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorktreeStatusChange {
+    pub status: WorktreeStatus,
+    pub changed_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorktreeGitStatus {
+    pub path: String,
+    pub branch: Option<String>,
+    pub head: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead_upstream: Option<u32>,
+    pub behind_upstream: Option<u32>,
+    pub base_ref: Option<String>,
+    pub ahead_base: Option<u32>,
+    pub behind_base: Option<u32>,
+    pub staged: u32,
+    pub unstaged: u32,
+    pub untracked: u32,
+    pub conflicted: Vec<String>,
+    pub operation: Option<GitOperation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BootstrapEventKind {
+    Copied,
+    Linked,
+    CommandStarted,
+    Stdout,
+    Stderr,
+    CommandFinished,
+    // The setup commands, one per line, wait for `approve_worktree_setup`.
+    ApprovalRequired,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorktreeBootstrapEvent {
+    pub worktree_path: String,
+    pub kind: BootstrapEventKind,
+    pub command: Option<String>,
+    pub message: String,
+}
+
+// `paths` are relative to the worktree, with '/' separators. `git_changed` is set when
+// HEAD, the index or refs moved, which can change status without touching a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorktreeChangedEvent {
+    pub worktree_path: String,
+    pub paths: Vec<String>,
+    pub git_changed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CleanupReason {
+    BranchMerged,
+    PullRequestMerged,
+    PullRequestClosed,
+    Stale,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CleanupOptions {
+    #[serde(default)]
+    pub stale_days: Option<u32>,
+    #[serde(default)]
+    pub delete_remote_branches: bool,
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupCandidate {
+    pub worktree_path: String,
+    pub branch_name: String,
+    pub reasons: Vec<CleanupReason>,
+    pub last_commit_at: Option<String>,
+    pub has_local_changes: bool,
+    pub will_remove: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CleanupReport {
+    pub dry_run: bool,
+    pub candidates: Vec<CleanupCandidate>,
+    pub removed_worktrees: Vec<String>,
+    pub deleted_branches: Vec<String>,
+    pub deleted_remote_branches: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncStrategy {
+    #[default]
+    Rebase,
+    Merge,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncStatus {
+    UpToDate,
+    Synced,
+    Conflicts,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictRegion {
+    pub start_line: u32,
+    pub separator_line: Option<u32>,
+    pub end_line: u32,
+}
+
+// Blob ids come from the index stages: 1 = common ancestor, 2 = "ours", 3 = "theirs".
+// During a rebase "ours" is the base being rebased onto and "theirs" the replayed commit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictFile {
+    pub path: String,
+    pub ancestor_blob: Option<String>,
+    pub ours_blob: Option<String>,
+    pub theirs_blob: Option<String>,
+    pub regions: Vec<ConflictRegion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncResult {
+    pub status: SyncStatus,
+    pub base_ref: String,
+    pub operation: Option<GitOperation>,
+    pub conflicts: Vec<ConflictFile>,
+}
@@ -1,10 +1,15 @@
-use crate::models::{AgentMode, AgentOutputEvent, AgentSession, AgentStatus, AgentStatusEvent};
+use crate::models::{
+    AgentMode, AgentOutputEvent, AgentSession, AgentStatus, AgentStatusEvent, RepositoryConfig,
+};
+use crate::services::{RepositoryConfigLoader, SessionStore, SettingsStore, WorktreeWatcher};
 use chrono::Utc;
 use portable_pty::{native_pty_system, Child, CommandBuilder, PtySize};
 use std::collections::HashMap;
 use std::io::{Read, Write};
+use std::path::Path;
 use std::sync::mpsc::{self, Sender};
-use tauri::{AppHandle, Emitter};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
 use uuid::Uuid;
 
 const AGENT_READ_BUFFER_SIZE: usize = 4096;
@@ -55,7 +60,9 @@ impl AgentManager {
             .openpty(size)
             .map_err(|e| format!("Failed to open PTY: {}", e))?;
 
-        let cmd = build_claude_command(&worktree_path, &mode);
+        let settings = SettingsStore::load(&app_handle)?;
+        let config = RepositoryConfigLoader::load(&app_handle, Path::new(&worktree_path))?;
+        let cmd = build_claude_command(&settings.claude_cli_path, &worktree_path, &mode, &config);
 
         let child = pair
             .slave
@@ -83,7 +90,12 @@ impl AgentManager {
             completed_at: None,
         };
 
-        spawn_output_reader(app_handle.clone(), session_id.clone(), reader);
+        spawn_output_reader(
+            app_handle.clone(),
+            session_id.clone(),
+            worktree_path.clone(),
+            reader,
+        );
         spawn_command_handler(pair.master, writer, command_rx);
 
         session.status = AgentStatus::Running;
@@ -96,6 +108,10 @@ impl AgentManager {
 
         self.sessions.insert(session_id.clone(), session_info);
 
+        if let Err(e) = SessionStore::record(&app_handle, &session) {
+            eprintln!("Failed to record agent session {}: {}", session_id, e);
+        }
+
         emit_status_change(&app_handle, &session_id, AgentStatus::Running);
 
         if !issue_context.is_empty() {
@@ -156,7 +172,6 @@ impl AgentManager {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn get_session(&self, session_id: &str) -> Option<&AgentSessionInfo> {
         self.sessions.get(session_id)
     }
@@ -174,10 +189,19 @@ impl Default for AgentManager {
     }
 }
 
-fn build_claude_command(worktree_path: &str, mode: &AgentMode) -> CommandBuilder {
-    let mut cmd = CommandBuilder::new("claude");
+fn build_claude_command(
+    claude_cli_path: &str,
+    worktree_path: &str,
+    mode: &AgentMode,
+    config: &RepositoryConfig,
+) -> CommandBuilder {
+    let mut cmd = CommandBuilder::new(claude_cli_path);
     cmd.cwd(worktree_path);
 
+    for (key, value) in config.env.iter().chain(config.agent.env.iter()) {
+        cmd.env(key, value);
+    }
+
     match mode {
         AgentMode::Plan => {
             cmd.arg("--plan");
@@ -185,10 +209,17 @@ fn build_claude_command(worktree_path: &str, mode: &AgentMode) -> CommandBuilder
         AgentMode::Act => {}
     }
 
+    for arg in &config.agent.args {
+        cmd.arg(arg);
+    }
+
     cmd
 }
 
 fn emit_status_change(app_handle: &AppHandle, session_id: &str, status: AgentStatus) {
+    if let Err(e) = SessionStore::update_status(app_handle, session_id, &status) {
+        eprintln!("Failed to persist agent status for {}: {}", session_id, e);
+    }
     let event = AgentStatusEvent {
         session_id: session_id.to_string(),
         status,
@@ -198,19 +229,21 @@ fn emit_status_change(app_handle: &AppHandle, session_id: &str, status: AgentSta
     }
 }
 
+// The reader holds the session's worktree watch, so the watch is released however the
+// session ends.
 fn spawn_output_reader(
     app_handle: AppHandle,
     session_id: String,
+    worktree_path: String,
     mut reader: Box<dyn Read + Send>,
 ) {
     std::thread::spawn(move || {
+        watch_worktree(&app_handle, &worktree_path, &session_id);
+
         let mut buffer = [0u8; AGENT_READ_BUFFER_SIZE];
-        loop {
+        let status = loop {
             match reader.read(&mut buffer) {
-                Ok(0) => {
-                    emit_status_change(&app_handle, &session_id, AgentStatus::Completed);
-                    break;
-                }
+                Ok(0) => break AgentStatus::Completed,
                 Ok(n) => {
                     let payload = AgentOutputEvent {
                         session_id: session_id.clone(),
@@ -218,20 +251,39 @@ fn spawn_output_reader(
                     };
                     if let Err(e) = app_handle.emit("agent-output", payload) {
                         eprintln!("Failed to emit agent-output event: {}", e);
-                        emit_status_change(&app_handle, &session_id, AgentStatus::Error);
-                        break;
+                        break AgentStatus::Error;
                     }
                 }
                 Err(e) => {
                     eprintln!("Agent read error for session {}: {}", session_id, e);
-                    emit_status_change(&app_handle, &session_id, AgentStatus::Error);
-                    break;
+                    break AgentStatus::Error;
                 }
             }
+        };
+        emit_status_change(&app_handle, &session_id, status);
+
+        if let Some(watcher) = app_handle.try_state::<Arc<Mutex<WorktreeWatcher>>>() {
+            if let Ok(mut watcher) = watcher.lock() {
+                watcher.unwatch(&worktree_path, &session_id);
+            }
         }
     });
 }
 
+// The agent rewrites files live; a failed watch only costs the live diff updates.
+fn watch_worktree(app_handle: &AppHandle, worktree_path: &str, session_id: &str) {
+    let Some(watcher) = app_handle.try_state::<Arc<Mutex<WorktreeWatcher>>>() else {
+        return;
+    };
+    let result = match watcher.lock() {
+        Ok(mut watcher) => watcher.watch(app_handle.clone(), worktree_path, session_id),
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to watch worktree {}: {}", worktree_path, e);
+    }
+}
+
 fn spawn_command_handler(
     master: Box<dyn portable_pty::MasterPty + Send>,
     mut writer: Box<dyn Write + Send>,
@@ -0,0 +1,62 @@
+use crate::models::{BoardConfig, KanbanColumn, SavedFilter};
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use super::database::Database;
+
+const COLUMNS_KEY: &str = "columns";
+const SAVED_FILTERS_KEY: &str = "saved_filters";
+
+pub struct BoardStore;
+
+impl BoardStore {
+    pub fn load(app: &AppHandle) -> Result<BoardConfig, String> {
+        Ok(BoardConfig {
+            columns: Self::read::<Vec<KanbanColumn>>(app, COLUMNS_KEY)?.unwrap_or_default(),
+            saved_filters: Self::read::<Vec<SavedFilter>>(app, SAVED_FILTERS_KEY)?
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn save(app: &AppHandle, config: &BoardConfig) -> Result<(), String> {
+        Self::write(app, COLUMNS_KEY, &config.columns)?;
+        Self::write(app, SAVED_FILTERS_KEY, &config.saved_filters)
+    }
+
+    fn read<T: DeserializeOwned>(app: &AppHandle, key: &str) -> Result<Option<T>, String> {
+        let database = Database::from_app(app)?;
+
+        let value: Option<String> = database.with_connection(|conn| {
+            conn.query_row(
+                "SELECT value FROM board_config WHERE key = ?1",
+                [key],
+                |row| row.get(0),
+            )
+            .optional()
+        })?;
+
+        value
+            .map(|v| serde_json::from_str(&v).map_err(|e| format!("Failed to deserialize: {}", e)))
+            .transpose()
+    }
+
+    fn write<T: Serialize>(app: &AppHandle, key: &str, value: &T) -> Result<(), String> {
+        let database = Database::from_app(app)?;
+        let value =
+            serde_json::to_string(value).map_err(|e| format!("Failed to serialize: {}", e))?;
+
+        database.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO board_config (key, value, updated_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(key) DO UPDATE SET
+                    value = excluded.value,
+                    updated_at = excluded.updated_at",
+                params![key, value, Utc::now().to_rfc3339()],
+            )
+            .map(|_| ())
+        })
+    }
+}
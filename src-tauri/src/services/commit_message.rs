@@ -0,0 +1,198 @@
+use crate::models::{CommitConfig, FileChangeStatus, FileChangeSummary};
+use std::path::Path;
+
+const BUILD_FILES: &[&str] = &[
+    "Cargo.toml",
+    "Cargo.lock",
+    "package.json",
+    "pnpm-lock.yaml",
+    "package-lock.json",
+    "yarn.lock",
+];
+
+pub struct CommitMessage;
+
+impl CommitMessage {
+    // Builds a conventional-commit style message from the staged changes. The type is
+    // guessed from which kinds of files changed; it is a starting point for the user to edit.
+    pub fn generate(changes: &[FileChangeSummary], issue_number: Option<i32>) -> String {
+        let commit_type = commit_type(changes);
+        let scope = common_scope(changes)
+            .map(|scope| format!("({})", scope))
+            .unwrap_or_default();
+
+        let mut message = format!("{}{}: {}", commit_type, scope, subject(changes));
+
+        if changes.len() > 1 {
+            message.push_str("\n\n");
+            for change in changes {
+                message.push_str(&format!("- {}\n", describe(change)));
+            }
+            message.truncate(message.trim_end().len());
+        }
+
+        if let Some(number) = issue_number {
+            message.push_str(&format!("\n\nRefs #{}", number));
+        }
+
+        message
+    }
+
+    pub fn lint(message: &str, config: &CommitConfig) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut lines = message.lines();
+        let subject = lines.next().unwrap_or_default().trim_end();
+
+        if subject.trim().is_empty() {
+            errors.push("Subject line must not be empty".to_string());
+            return errors;
+        }
+
+        let length = subject.chars().count();
+        if length > config.max_subject_length {
+            errors.push(format!(
+                "Subject line is {} characters; the limit is {}",
+                length, config.max_subject_length
+            ));
+        }
+        if subject.ends_with('.') {
+            errors.push("Subject line must not end with a period".to_string());
+        }
+        if lines.next().is_some_and(|line| !line.trim().is_empty()) {
+            errors.push("Separate the subject from the body with a blank line".to_string());
+        }
+
+        if config.conventional {
+            if let Some(error) = lint_conventional(subject, &config.types) {
+                errors.push(error);
+            }
+        }
+
+        if config.require_issue_reference && !has_issue_reference(message) {
+            errors.push("Message must reference an issue (e.g. \"Refs #123\")".to_string());
+        }
+
+        errors
+    }
+}
+
+fn commit_type(changes: &[FileChangeSummary]) -> &'static str {
+    let all = |predicate: fn(&str) -> bool| changes.iter().all(|c| predicate(&c.path));
+
+    if changes.is_empty() {
+        "chore"
+    } else if all(is_docs) {
+        "docs"
+    } else if all(is_test) {
+        "test"
+    } else if all(is_build) {
+        "build"
+    } else if all(is_ci) {
+        "ci"
+    } else if changes.iter().any(|c| c.status == FileChangeStatus::Added) {
+        "feat"
+    } else {
+        "fix"
+    }
+}
+
+fn is_docs(path: &str) -> bool {
+    path.ends_with(".md") || path.starts_with("docs/")
+}
+
+fn is_test(path: &str) -> bool {
+    path.contains("/tests/")
+        || path.starts_with("tests/")
+        || path.contains(".test.")
+        || path.contains(".spec.")
+        || path.ends_with("_test.rs")
+}
+
+fn is_build(path: &str) -> bool {
+    file_name(path).is_some_and(|name| BUILD_FILES.contains(&name))
+}
+
+fn is_ci(path: &str) -> bool {
+    path.starts_with(".github/")
+}
+
+fn file_name(path: &str) -> Option<&str> {
+    Path::new(path).file_name().and_then(|n| n.to_str())
+}
+
+// The first directory shared by every changed path, if there is one.
+fn common_scope(changes: &[FileChangeSummary]) -> Option<String> {
+    let first = changes.first()?.path.split('/').next()?;
+    let shared = changes.iter().all(|change| {
+        let mut parts = change.path.split('/');
+        parts.next() == Some(first) && parts.next().is_some()
+    });
+    shared.then(|| first.trim_start_matches('.').to_string())
+}
+
+fn subject(changes: &[FileChangeSummary]) -> String {
+    match changes {
+        [] => "update files".to_string(),
+        [change] => {
+            let name = file_name(&change.path).unwrap_or(&change.path);
+            match change.status {
+                FileChangeStatus::Added => format!("add {}", name),
+                FileChangeStatus::Deleted => format!("remove {}", name),
+                FileChangeStatus::Renamed => format!("rename {}", name),
+                _ => format!("update {}", name),
+            }
+        }
+        changes => format!("update {} files", changes.len()),
+    }
+}
+
+fn describe(change: &FileChangeSummary) -> String {
+    let verb = match change.status {
+        FileChangeStatus::Added => "add",
+        FileChangeStatus::Deleted => "remove",
+        FileChangeStatus::Renamed => "rename",
+        FileChangeStatus::Copied => "copy",
+        _ => "update",
+    };
+    match (change.additions, change.deletions) {
+        (Some(added), Some(deleted)) => {
+            format!("{} {} (+{} -{})", verb, change.path, added, deleted)
+        }
+        _ => format!("{} {}", verb, change.path),
+    }
+}
+
+fn lint_conventional(subject: &str, types: &[String]) -> Option<String> {
+    let Some((header, description)) = subject.split_once(": ") else {
+        return Some("Subject must follow \"type(scope): description\"".to_string());
+    };
+
+    let header = header.strip_suffix('!').unwrap_or(header);
+    let commit_type = match header.split_once('(') {
+        Some((commit_type, scope)) if scope.ends_with(')') && scope.len() > 1 => commit_type,
+        Some(_) => return Some(format!("Malformed scope in {:?}", header)),
+        None => header,
+    };
+
+    if !types.iter().any(|t| t == commit_type) {
+        return Some(format!(
+            "Unknown commit type {:?} (expected one of: {})",
+            commit_type,
+            types.join(", ")
+        ));
+    }
+    if description.trim().is_empty() {
+        return Some("Commit description must not be empty".to_string());
+    }
+
+    None
+}
+
+fn has_issue_reference(message: &str) -> bool {
+    message.match_indices('#').any(|(index, _)| {
+        message[index + 1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_digit())
+    })
+}
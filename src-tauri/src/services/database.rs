@@ -0,0 +1,264 @@
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_store::StoreExt;
+
+use crate::models::Repository;
+
+const DATABASE_FILE: &str = "marionette.db";
+const LEGACY_REPOSITORIES_STORE: &str = "repositories.json";
+const LEGACY_REPOSITORIES_KEY: &str = "repositories";
+const LEGACY_IMPORT_META_KEY: &str = "legacy_store_imported";
+
+// Each entry is applied once, in order; `PRAGMA user_version` records how many have run.
+// Never edit a migration that has shipped - append a new one instead.
+const MIGRATIONS: &[&str] = &[
+    r#"
+    CREATE TABLE meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+
+    CREATE TABLE repositories (
+        id TEXT PRIMARY KEY,
+        owner TEXT NOT NULL,
+        name TEXT NOT NULL,
+        full_name TEXT NOT NULL,
+        local_path TEXT NOT NULL,
+        default_branch TEXT NOT NULL,
+        is_private INTEGER NOT NULL,
+        account_id TEXT,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL
+    );
+
+    CREATE TABLE worktrees (
+        id TEXT PRIMARY KEY,
+        repository_id TEXT NOT NULL,
+        issue_number INTEGER,
+        branch_name TEXT NOT NULL,
+        path TEXT NOT NULL UNIQUE,
+        status TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL
+    );
+    CREATE INDEX idx_worktrees_repository ON worktrees (repository_id);
+
+    CREATE TABLE agent_sessions (
+        id TEXT PRIMARY KEY,
+        worktree_path TEXT NOT NULL,
+        mode TEXT NOT NULL,
+        status TEXT NOT NULL,
+        started_at TEXT NOT NULL,
+        completed_at TEXT
+    );
+    CREATE INDEX idx_agent_sessions_worktree ON agent_sessions (worktree_path);
+
+    CREATE TABLE issue_cache (
+        repository TEXT NOT NULL,
+        number INTEGER NOT NULL,
+        state TEXT NOT NULL,
+        data TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        fetched_at TEXT NOT NULL,
+        PRIMARY KEY (repository, number)
+    );
+
+    CREATE TABLE settings (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL,
+        updated_at TEXT NOT NULL
+    );
+    "#,
+    r#"
+    CREATE TABLE board_config (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL,
+        updated_at TEXT NOT NULL
+    );
+    "#,
+    r#"
+    ALTER TABLE worktrees ADD COLUMN base_ref TEXT;
+    ALTER TABLE worktrees ADD COLUMN pull_request_number INTEGER;
+    ALTER TABLE worktrees ADD COLUMN pull_request_url TEXT;
+
+    CREATE TABLE worktree_status_history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        worktree_id TEXT NOT NULL,
+        status TEXT NOT NULL,
+        changed_at TEXT NOT NULL
+    );
+    CREATE INDEX idx_worktree_status_history_worktree ON worktree_status_history (worktree_id);
+    "#,
+    r#"
+    CREATE TABLE setup_approvals (
+        repository_path TEXT NOT NULL,
+        commands_hash TEXT NOT NULL,
+        approved_at TEXT NOT NULL,
+        PRIMARY KEY (repository_path, commands_hash)
+    );
+    "#,
+];
+
+pub struct Database {
+    connection: Mutex<Connection>,
+}
+
+impl Database {
+    pub fn open(app: &AppHandle) -> Result<Self, String> {
+        let data_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+
+        fs::create_dir_all(&data_dir)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+        let connection = Connection::open(data_dir.join(DATABASE_FILE))
+            .map_err(|e| format!("Failed to open database: {}", e))?;
+
+        Self::from_connection(connection)
+    }
+
+    pub fn from_connection(mut connection: Connection) -> Result<Self, String> {
+        connection
+            .execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")
+            .map_err(|e| format!("Failed to configure database: {}", e))?;
+
+        Self::run_migrations(&mut connection)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    pub fn from_app(app: &AppHandle) -> Result<State<'_, Database>, String> {
+        app.try_state::<Database>()
+            .ok_or_else(|| "Database is not initialized".to_string())
+    }
+
+    fn run_migrations(connection: &mut Connection) -> Result<(), String> {
+        let version: usize = connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(|e| format!("Failed to read schema version: {}", e))?;
+
+        if version > MIGRATIONS.len() {
+            return Err(format!(
+                "Database schema version {} is newer than this build supports ({})",
+                version,
+                MIGRATIONS.len()
+            ));
+        }
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let tx = connection
+                .transaction()
+                .map_err(|e| format!("Failed to start migration: {}", e))?;
+            tx.execute_batch(migration)
+                .map_err(|e| format!("Migration {} failed: {}", index + 1, e))?;
+            tx.pragma_update(None, "user_version", index + 1)
+                .map_err(|e| format!("Failed to record schema version: {}", e))?;
+            tx.commit()
+                .map_err(|e| format!("Failed to commit migration: {}", e))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn with_connection<T>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>,
+    ) -> Result<T, String> {
+        let mut connection = self
+            .connection
+            .lock()
+            .map_err(|e| format!("Failed to lock database: {}", e))?;
+        f(&mut connection).map_err(|e| format!("Database error: {}", e))
+    }
+
+    pub fn get_meta(&self, key: &str) -> Result<Option<String>, String> {
+        self.with_connection(|conn| {
+            conn.query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+                row.get(0)
+            })
+            .optional()
+        })
+    }
+
+    pub fn set_meta(&self, key: &str, value: &str) -> Result<(), String> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO meta (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![key, value],
+            )
+            .map(|_| ())
+        })
+    }
+
+    // Copies data written by the tauri-plugin-store based persistence into SQLite once.
+    // The legacy JSON files are left in place so a downgrade still finds them.
+    pub fn import_legacy_stores(&self, app: &AppHandle) -> Result<(), String> {
+        if self.get_meta(LEGACY_IMPORT_META_KEY)?.is_some() {
+            return Ok(());
+        }
+
+        let store = app
+            .store(LEGACY_REPOSITORIES_STORE)
+            .map_err(|e| format!("Failed to open store: {}", e))?;
+
+        let repositories: Vec<Repository> = match store.get(LEGACY_REPOSITORIES_KEY) {
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|e| format!("Failed to deserialize: {}", e))?,
+            None => vec![],
+        };
+
+        self.with_connection(|conn| {
+            let tx = conn.transaction()?;
+            for repository in &repositories {
+                tx.execute(
+                    "INSERT OR IGNORE INTO repositories
+                     (id, owner, name, full_name, local_path, default_branch, is_private,
+                      account_id, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![
+                        repository.id,
+                        repository.owner,
+                        repository.name,
+                        repository.full_name,
+                        repository.local_path,
+                        repository.default_branch,
+                        repository.is_private,
+                        repository.account_id,
+                        repository.created_at,
+                        repository.updated_at,
+                    ],
+                )?;
+            }
+            tx.execute(
+                "INSERT INTO meta (key, value) VALUES (?1, ?2)",
+                params![LEGACY_IMPORT_META_KEY, chrono::Utc::now().to_rfc3339()],
+            )?;
+            tx.commit()
+        })
+    }
+}
+
+pub(crate) fn to_sql_text<T: Serialize>(value: &T) -> Result<String, String> {
+    match serde_json::to_value(value).map_err(|e| format!("Failed to serialize: {}", e))? {
+        serde_json::Value::String(text) => Ok(text),
+        other => Ok(other.to_string()),
+    }
+}
+
+pub(crate) fn from_sql_text<T: DeserializeOwned>(
+    column: usize,
+    text: String,
+) -> rusqlite::Result<T> {
+    serde_json::from_value(serde_json::Value::String(text))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(column, Type::Text, Box::new(e)))
+}
@@ -0,0 +1,394 @@
+use crate::models::{
+    DiffHunk, DiffLine, DiffLineKind, DiffStats, FileChangeStatus, FileDiff, StructuredDiff,
+};
+
+pub struct DiffParser;
+
+impl DiffParser {
+    // Parses `git diff -p` output (run with `core.quotePath=false` and the default `a/` and
+    // `b/` prefixes) into per-file diffs.
+    pub fn parse(patch: &str) -> StructuredDiff {
+        let mut files: Vec<FileDiff> = Vec::new();
+        // Split on '\n' only: `str::lines` would also drop the '\r' of CRLF content lines.
+        let patch = patch.strip_suffix('\n').unwrap_or(patch);
+        let mut lines = patch.split('\n').peekable();
+
+        while let Some(line) = lines.next() {
+            let Some(paths) = line.strip_prefix("diff --git ") else {
+                continue;
+            };
+
+            let (old_path, new_path) = split_diff_git_paths(paths);
+            let mut file = FileDiff {
+                path: new_path.unwrap_or_default(),
+                old_path,
+                status: FileChangeStatus::Modified,
+                old_mode: None,
+                new_mode: None,
+                similarity: None,
+                is_binary: false,
+                additions: 0,
+                deletions: 0,
+                hunks: Vec::new(),
+            };
+
+            // Extended header lines up to the first hunk or the next file.
+            while let Some(&header) = lines.peek() {
+                if header.starts_with("diff --git ") || header.starts_with("@@") {
+                    break;
+                }
+                lines.next();
+                apply_header_line(&mut file, header);
+            }
+
+            while let Some(&hunk_header) = lines.peek() {
+                if !hunk_header.starts_with("@@") {
+                    break;
+                }
+                lines.next();
+                let Some(mut hunk) = parse_hunk_header(hunk_header) else {
+                    continue;
+                };
+
+                let mut old_line = hunk.old_start;
+                let mut new_line = hunk.new_start;
+                while let Some(&body) = lines.peek() {
+                    let (kind, content) = match body.chars().next() {
+                        Some('+') => (DiffLineKind::Addition, &body[1..]),
+                        Some('-') => (DiffLineKind::Deletion, &body[1..]),
+                        Some(' ') => (DiffLineKind::Context, &body[1..]),
+                        Some('\\') => (DiffLineKind::NoNewline, body),
+                        None => (DiffLineKind::Context, ""),
+                        _ => break,
+                    };
+                    lines.next();
+
+                    let line = match kind {
+                        DiffLineKind::Addition => {
+                            file.additions += 1;
+                            new_line += 1;
+                            DiffLine {
+                                kind,
+                                old_line: None,
+                                new_line: Some(new_line - 1),
+                                content: content.to_string(),
+                            }
+                        }
+                        DiffLineKind::Deletion => {
+                            file.deletions += 1;
+                            old_line += 1;
+                            DiffLine {
+                                kind,
+                                old_line: Some(old_line - 1),
+                                new_line: None,
+                                content: content.to_string(),
+                            }
+                        }
+                        DiffLineKind::Context => {
+                            old_line += 1;
+                            new_line += 1;
+                            DiffLine {
+                                kind,
+                                old_line: Some(old_line - 1),
+                                new_line: Some(new_line - 1),
+                                content: content.to_string(),
+                            }
+                        }
+                        DiffLineKind::NoNewline => DiffLine {
+                            kind,
+                            old_line: None,
+                            new_line: None,
+                            content: content.to_string(),
+                        },
+                    };
+                    hunk.lines.push(line);
+                }
+
+                file.hunks.push(hunk);
+            }
+
+            if file.old_path.as_deref() == Some(file.path.as_str())
+                && !matches!(
+                    file.status,
+                    FileChangeStatus::Renamed | FileChangeStatus::Copied
+                )
+            {
+                file.old_path = None;
+            }
+            if file.status == FileChangeStatus::Modified
+                && file.hunks.is_empty()
+                && !file.is_binary
+                && file.old_mode != file.new_mode
+            {
+                file.status = FileChangeStatus::ModeChanged;
+            }
+
+            files.push(file);
+        }
+
+        let stats = DiffStats {
+            files_changed: files.len() as u32,
+            additions: files.iter().map(|f| f.additions).sum(),
+            deletions: files.iter().map(|f| f.deletions).sum(),
+        };
+
+        StructuredDiff { files, stats }
+    }
+}
+
+fn apply_header_line(file: &mut FileDiff, line: &str) {
+    if let Some(mode) = line.strip_prefix("new file mode ") {
+        file.status = FileChangeStatus::Added;
+        file.new_mode = Some(mode.to_string());
+    } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+        file.status = FileChangeStatus::Deleted;
+        file.old_mode = Some(mode.to_string());
+    } else if let Some(mode) = line.strip_prefix("old mode ") {
+        file.old_mode = Some(mode.to_string());
+    } else if let Some(mode) = line.strip_prefix("new mode ") {
+        file.new_mode = Some(mode.to_string());
+    } else if let Some(path) = line.strip_prefix("rename from ") {
+        file.status = FileChangeStatus::Renamed;
+        file.old_path = Some(path.to_string());
+    } else if let Some(path) = line.strip_prefix("rename to ") {
+        file.path = path.to_string();
+    } else if let Some(path) = line.strip_prefix("copy from ") {
+        file.status = FileChangeStatus::Copied;
+        file.old_path = Some(path.to_string());
+    } else if let Some(path) = line.strip_prefix("copy to ") {
+        file.path = path.to_string();
+    } else if let Some(value) = line.strip_prefix("similarity index ") {
+        file.similarity = value.trim_end_matches('%').parse().ok();
+    } else if let Some(index) = line.strip_prefix("index ") {
+        // "index <old>..<new> <mode>" carries the mode when it did not change.
+        if let Some((_, mode)) = index.split_once(' ') {
+            file.old_mode.get_or_insert_with(|| mode.to_string());
+            file.new_mode.get_or_insert_with(|| mode.to_string());
+        }
+    } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+        file.is_binary = true;
+    } else if let Some(path) = line.strip_prefix("--- ") {
+        // git terminates paths containing spaces with a tab on these two lines.
+        if let Some(path) = path.trim_end_matches('\t').strip_prefix("a/") {
+            file.old_path = Some(path.to_string());
+        }
+    } else if let Some(path) = line.strip_prefix("+++ ") {
+        if let Some(path) = path.trim_end_matches('\t').strip_prefix("b/") {
+            file.path = path.to_string();
+        }
+    }
+}
+
+// "a/<old> b/<new>" cannot be split on the space when paths contain spaces, but the two
+// halves are identical unless the file was renamed (which is covered by the rename
+// headers), so the midpoint split is tried first.
+fn split_diff_git_paths(paths: &str) -> (Option<String>, Option<String>) {
+    if paths.len() % 2 == 1 {
+        let middle = paths.len() / 2;
+        if paths.is_char_boundary(middle) {
+            let (old, new) = (&paths[..middle], &paths[middle + 1..]);
+            if let (Some(old), Some(new)) = (old.strip_prefix("a/"), new.strip_prefix("b/")) {
+                if old == new {
+                    return (Some(old.to_string()), Some(new.to_string()));
+                }
+            }
+        }
+    }
+
+    match paths.split_once(" b/") {
+        Some((old, new)) => (
+            old.strip_prefix("a/").map(str::to_string),
+            Some(new.to_string()),
+        ),
+        None => (None, None),
+    }
+}
+
+// "@@ -<old_start>[,<old_lines>] +<new_start>[,<new_lines>] @@[ <section>]"
+fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let rest = line.strip_prefix("@@ ")?;
+    let (ranges, section) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(' ')?;
+
+    let parse_range = |range: &str| -> Option<(u32, u32)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = parse_range(old.strip_prefix('-')?)?;
+    let (new_start, new_lines) = parse_range(new.strip_prefix('+')?)?;
+
+    let section = section.trim();
+
+    Some(DiffHunk {
+        header: line.to_string(),
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        section: (!section.is_empty()).then(|| section.to_string()),
+        lines: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patch(lines: &[&str]) -> String {
+        format!("{}\n", lines.join("\n"))
+    }
+
+    #[test]
+    fn parses_rename_with_changes() {
+        let diff = DiffParser::parse(&patch(&[
+            "diff --git a/old.txt b/new.txt",
+            "similarity index 82%",
+            "rename from old.txt",
+            "rename to new.txt",
+            "index b566061..10f8ee7 100644",
+            "--- a/old.txt",
+            "+++ b/new.txt",
+            "@@ -3,4 +3,4 @@ two",
+            " three",
+            " four",
+            " five",
+            "-six",
+            "+six!",
+        ]));
+
+        let file = &diff.files[0];
+        assert_eq!(file.status, FileChangeStatus::Renamed);
+        assert_eq!(file.path, "new.txt");
+        assert_eq!(file.old_path.as_deref(), Some("old.txt"));
+        assert_eq!(file.similarity, Some(82));
+        assert_eq!((file.additions, file.deletions), (1, 1));
+
+        let hunk = &file.hunks[0];
+        assert_eq!((hunk.old_start, hunk.old_lines), (3, 4));
+        assert_eq!(hunk.section.as_deref(), Some("two"));
+        let added = &hunk.lines[4];
+        assert_eq!(added.kind, DiffLineKind::Addition);
+        assert_eq!((added.old_line, added.new_line), (None, Some(6)));
+        assert_eq!(added.content, "six!");
+    }
+
+    #[test]
+    fn parses_binary_file() {
+        let diff = DiffParser::parse(&patch(&[
+            "diff --git a/bin.dat b/bin.dat",
+            "index d5d0b8b..4a27031 100644",
+            "Binary files a/bin.dat and b/bin.dat differ",
+        ]));
+
+        let file = &diff.files[0];
+        assert!(file.is_binary);
+        assert_eq!(file.status, FileChangeStatus::Modified);
+        assert!(file.hunks.is_empty());
+    }
+
+    #[test]
+    fn parses_mode_only_change() {
+        let diff = DiffParser::parse(&patch(&[
+            "diff --git a/run.sh b/run.sh",
+            "old mode 100644",
+            "new mode 100755",
+        ]));
+
+        let file = &diff.files[0];
+        assert_eq!(file.status, FileChangeStatus::ModeChanged);
+        assert_eq!(file.old_mode.as_deref(), Some("100644"));
+        assert_eq!(file.new_mode.as_deref(), Some("100755"));
+        assert_eq!(file.old_path, None);
+    }
+
+    #[test]
+    fn keeps_carriage_returns() {
+        let diff = DiffParser::parse(&patch(&[
+            "diff --git a/crlf.txt b/crlf.txt",
+            "index c30dea8..57213eb 100644",
+            "--- a/crlf.txt",
+            "+++ b/crlf.txt",
+            "@@ -1,2 +1,2 @@",
+            " a\r",
+            "-b\r",
+            "+B\r",
+        ]));
+
+        let contents: Vec<&str> = diff.files[0].hunks[0]
+            .lines
+            .iter()
+            .map(|line| line.content.as_str())
+            .collect();
+        assert_eq!(contents, ["a\r", "b\r", "B\r"]);
+    }
+
+    #[test]
+    fn parses_missing_newline_marker() {
+        let diff = DiffParser::parse(&patch(&[
+            "diff --git a/nonl.txt b/nonl.txt",
+            "index 1c1206e..b25fa3f 100644",
+            "--- a/nonl.txt",
+            "+++ b/nonl.txt",
+            "@@ -1 +1 @@",
+            "-last",
+            "\\ No newline at end of file",
+            "+last",
+        ]));
+
+        let hunk = &diff.files[0].hunks[0];
+        assert_eq!((hunk.old_lines, hunk.new_lines), (1, 1));
+        let kinds: Vec<DiffLineKind> = hunk.lines.iter().map(|line| line.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                DiffLineKind::Deletion,
+                DiffLineKind::NoNewline,
+                DiffLineKind::Addition
+            ]
+        );
+        assert_eq!(
+            (hunk.lines[1].old_line, hunk.lines[1].new_line),
+            (None, None)
+        );
+        assert_eq!(hunk.lines[2].new_line, Some(1));
+    }
+
+    #[test]
+    fn parses_paths_with_spaces_and_counts_files() {
+        let diff = DiffParser::parse(&patch(&[
+            "diff --git a/my file.txt b/my file.txt",
+            "new file mode 100644",
+            "index 0000000..ce01362",
+            "--- /dev/null",
+            "+++ b/my file.txt\t",
+            "@@ -0,0 +1 @@",
+            "+hello",
+            "diff --git a/gone.txt b/gone.txt",
+            "deleted file mode 100644",
+            "index ce01362..0000000",
+            "--- a/gone.txt",
+            "+++ /dev/null",
+            "@@ -1 +0,0 @@",
+            "-bye",
+        ]));
+
+        let [added, deleted] = diff.files.as_slice() else {
+            panic!("expected two files, got {}", diff.files.len());
+        };
+        assert_eq!(added.status, FileChangeStatus::Added);
+        assert_eq!(added.path, "my file.txt");
+        assert_eq!(added.old_path, None);
+        assert_eq!(deleted.status, FileChangeStatus::Deleted);
+        assert_eq!(deleted.path, "gone.txt");
+        assert_eq!(
+            (
+                diff.stats.files_changed,
+                diff.stats.additions,
+                diff.stats.deletions
+            ),
+            (2, 1, 1)
+        );
+    }
+}
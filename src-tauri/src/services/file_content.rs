@@ -0,0 +1,126 @@
+use crate::models::{FileContent, FileContentKind};
+use base64::Engine;
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+use std::path::Path;
+
+pub const MAX_TEXT_SIZE: u64 = 2 * 1024 * 1024;
+pub const MAX_IMAGE_SIZE: u64 = 10 * 1024 * 1024;
+
+// git looks for a NUL byte in the first 8000 bytes to decide a blob is binary.
+const BINARY_SNIFF_LENGTH: usize = 8000;
+
+pub struct FileContentLoader;
+
+impl FileContentLoader {
+    // The largest blob worth loading for `path`; anything bigger is reported as too large.
+    pub fn size_limit(path: &str) -> u64 {
+        if image_mime_type(path).is_some() {
+            MAX_IMAGE_SIZE
+        } else {
+            MAX_TEXT_SIZE
+        }
+    }
+
+    pub fn too_large(path: &str, size: u64) -> FileContent {
+        FileContent {
+            kind: FileContentKind::TooLarge,
+            size,
+            encoding: None,
+            mime_type: image_mime_type(path).map(str::to_string),
+            content: None,
+        }
+    }
+
+    pub fn from_bytes(path: &str, bytes: &[u8]) -> FileContent {
+        let size = bytes.len() as u64;
+        if size > Self::size_limit(path) {
+            return Self::too_large(path, size);
+        }
+
+        // SVG stays text so it can still be diffed line by line.
+        if let Some(mime_type) = image_mime_type(path).filter(|m| *m != "image/svg+xml") {
+            return FileContent {
+                kind: FileContentKind::Image,
+                size,
+                encoding: Some("base64".to_string()),
+                mime_type: Some(mime_type.to_string()),
+                content: Some(base64::engine::general_purpose::STANDARD.encode(bytes)),
+            };
+        }
+
+        // UTF-16 files contain NUL bytes, so the BOM has to be checked first.
+        let bom = Encoding::for_bom(bytes);
+        if bom.is_none() && is_binary(bytes) {
+            return FileContent {
+                kind: FileContentKind::Binary,
+                size,
+                encoding: None,
+                mime_type: None,
+                content: None,
+            };
+        }
+
+        // Legacy encodings such as Shift_JIS or EUC-JP are detected from the content; text
+        // the detected encoding cannot decode cleanly is reported like binary content.
+        let encoding = match bom {
+            Some((encoding, _)) => encoding,
+            None if std::str::from_utf8(bytes).is_ok() => UTF_8,
+            None => {
+                let mut detector = EncodingDetector::new();
+                detector.feed(bytes, true);
+                detector.guess(None, false)
+            }
+        };
+        let (text, had_errors) = encoding.decode_with_bom_removal(bytes);
+        if had_errors {
+            return FileContent {
+                kind: FileContentKind::Binary,
+                size,
+                encoding: None,
+                mime_type: None,
+                content: None,
+            };
+        }
+
+        FileContent {
+            kind: FileContentKind::Text,
+            size,
+            encoding: Some(encoding.name().to_string()),
+            mime_type: image_mime_type(path).map(str::to_string),
+            content: Some(text.into_owned()),
+        }
+    }
+
+    // A symlink is shown as its target path, which is what git stores for it.
+    pub fn symlink(target: &Path) -> FileContent {
+        let target = target.to_string_lossy().to_string();
+        FileContent {
+            kind: FileContentKind::Text,
+            size: target.len() as u64,
+            encoding: Some(UTF_8.name().to_string()),
+            mime_type: None,
+            content: Some(target),
+        }
+    }
+}
+
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_SNIFF_LENGTH)].contains(&0)
+}
+
+fn image_mime_type(path: &str) -> Option<&'static str> {
+    let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+    let mime_type = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        "avif" => "image/avif",
+        "svg" => "image/svg+xml",
+        _ => return None,
+    };
+    Some(mime_type)
+}
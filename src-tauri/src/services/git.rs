@@ -1,5 +1,31 @@
-use std::path::Path;
-use std::process::Command;
+use crate::models::{
+    CloneOptions, CommitInfo, ConflictFile, ConflictRegion, DiffOptions, DiffScope, DiffStats,
+    FileChangeStatus, FileChangeSummary, GitOperation, ResetMode, StructuredDiff, SyncStrategy,
+    WhitespaceMode, WorktreeGitStatus,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::diff_parser::DiffParser;
+use super::git_auth::GitCredentials;
+use super::git_command::{GitCommand, GitError, GitOutput};
+use super::git_reader::{GitReadBackend, GitReader};
+
+pub const DEFAULT_REMOTE: &str = "origin";
+
+// The well-known id of the empty tree, used as the parent of root commits.
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+// Patches are parsed and applied with the default `a/` and `b/` prefixes, whatever the
+// user's `diff.noprefix` or `diff.mnemonicPrefix` say.
+const DIFF_PREFIX_ARGS: [&str; 2] = ["--src-prefix=a/", "--dst-prefix=b/"];
+
+// Checkouts, rebases and commits touch the whole working tree and may run hooks, which
+// can take minutes in a large monorepo.
+const WORKING_TREE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const CLONE_TIMEOUT: Duration = Duration::from_secs(60 * 60);
 
 #[derive(Debug, Clone)]
 pub struct WorktreeInfo {
@@ -12,95 +38,307 @@ pub struct WorktreeInfo {
 pub struct GitService;
 
 impl GitService {
-    pub fn worktree_add(
+    pub async fn worktree_add(
         repo_path: &Path,
         worktree_path: &Path,
         branch_name: &str,
-    ) -> Result<(), String> {
-        let output = Command::new("git")
-            .current_dir(repo_path)
-            .args(["worktree", "add", "-b", branch_name])
-            .arg(worktree_path)
-            .output()
-            .map_err(|e| e.to_string())?;
-
-        if output.status.success() {
-            Ok(())
+        start_point: &str,
+        track: bool,
+    ) -> Result<(), GitError> {
+        let mut cmd = GitCommand::new(repo_path);
+        cmd.args(["worktree", "add"]);
+        if track {
+            cmd.arg("--track");
         } else {
-            Err(String::from_utf8_lossy(&output.stderr).to_string())
+            cmd.arg("--no-track");
         }
+        cmd.args(["-b", branch_name])
+            .arg(worktree_path)
+            .arg(start_point)
+            .timeout(WORKING_TREE_TIMEOUT)
+            .exclusive_repository()
+            .run()
+            .await?;
+
+        Ok(())
     }
 
-    pub fn worktree_list(repo_path: &Path) -> Result<Vec<String>, String> {
-        let output = Command::new("git")
-            .current_dir(repo_path)
-            .args(["worktree", "list", "--porcelain"])
+    pub async fn fetch(
+        repo_path: &Path,
+        remote: &str,
+        branch: &str,
+        credentials: Option<&GitCredentials>,
+    ) -> Result<(), GitError> {
+        let refspec = format!("+refs/heads/{0}:refs/remotes/{1}/{0}", branch, remote);
+
+        remote_command(repo_path, credentials)
+            .args(["fetch", "--no-tags", remote, &refspec])
+            .timeout(NETWORK_TIMEOUT)
+            .run()
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remote_branch_exists(
+        repo_path: &Path,
+        remote: &str,
+        branch: &str,
+    ) -> Result<bool, GitError> {
+        let name = format!("refs/remotes/{}/{}", remote, branch);
+        if let Some(exists) = read_in_process(repo_path, move |path| {
+            GitReader::reference_exists(path, &name)
+        })
+        .await
+        {
+            return Ok(exists);
+        }
+
+        let output = GitCommand::new(repo_path)
+            .args([
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("refs/remotes/{}/{}", remote, branch),
+            ])
             .output()
-            .map_err(|e| e.to_string())?;
-
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let worktrees: Vec<String> = stdout
-                .lines()
-                .filter(|line| line.starts_with("worktree "))
-                .map(|line| line.trim_start_matches("worktree ").to_string())
-                .collect();
-            Ok(worktrees)
-        } else {
-            Err(String::from_utf8_lossy(&output.stderr).to_string())
+            .await?;
+
+        Ok(output.success())
+    }
+
+    pub async fn worktree_list(repo_path: &Path) -> Result<Vec<String>, GitError> {
+        if let Some(worktrees) = read_in_process(repo_path, GitReader::worktrees).await {
+            return Ok(worktrees
+                .into_iter()
+                .map(|worktree| worktree.path)
+                .collect());
         }
+
+        let stdout = GitCommand::new(repo_path)
+            .args(["worktree", "list", "--porcelain"])
+            .run()
+            .await?;
+
+        Ok(stdout
+            .lines()
+            .filter(|line| line.starts_with("worktree "))
+            .map(|line| line.trim_start_matches("worktree ").to_string())
+            .collect())
     }
 
-    pub fn worktree_remove(
+    pub async fn worktree_remove(
         repo_path: &Path,
         worktree_path: &Path,
         force: bool,
-    ) -> Result<(), String> {
-        let mut args = vec!["worktree", "remove"];
+    ) -> Result<(), GitError> {
+        let mut cmd = GitCommand::new(repo_path);
+        cmd.args(["worktree", "remove"]);
         if force {
-            args.push("--force");
+            cmd.arg("--force");
         }
+        cmd.arg(worktree_path)
+            .timeout(WORKING_TREE_TIMEOUT)
+            .exclusive_repository()
+            .run()
+            .await?;
 
-        let output = Command::new("git")
-            .current_dir(repo_path)
-            .args(&args)
-            .arg(worktree_path)
+        Ok(())
+    }
+
+    pub async fn worktree_prune(repo_path: &Path) -> Result<(), GitError> {
+        GitCommand::new(repo_path)
+            .args(["worktree", "prune"])
+            .exclusive_repository()
+            .run()
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn is_ancestor(repo_path: &Path, commit: &str, base: &str) -> Result<bool, GitError> {
+        let output = GitCommand::new(repo_path)
+            .args(["merge-base", "--is-ancestor", commit, base])
             .output()
-            .map_err(|e| e.to_string())?;
+            .await?;
 
-        if output.status.success() {
-            Ok(())
-        } else {
-            Err(String::from_utf8_lossy(&output.stderr).to_string())
+        match output.code() {
+            Some(0) => Ok(true),
+            Some(1) => Ok(false),
+            _ => Err(output.into_error()),
         }
     }
 
-    pub fn diff(repo_path: &Path) -> Result<String, String> {
-        let output = Command::new("git")
-            .current_dir(repo_path)
+    pub async fn last_commit_date(
+        repo_path: &Path,
+        reference: &str,
+    ) -> Result<Option<String>, GitError> {
+        let stdout = GitCommand::new(repo_path)
+            .args(["log", "-1", "--format=%cI", reference, "--"])
+            .run()
+            .await?;
+
+        let date = stdout.trim().to_string();
+        Ok(Some(date).filter(|d| !d.is_empty()))
+    }
+
+    pub async fn delete_branch(
+        repo_path: &Path,
+        branch_name: &str,
+        force: bool,
+    ) -> Result<(), GitError> {
+        let flag = if force { "-D" } else { "-d" };
+        GitCommand::new(repo_path)
+            .args(["branch", flag, branch_name])
+            .exclusive_repository()
+            .run()
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_remote_branch(
+        repo_path: &Path,
+        remote: &str,
+        branch_name: &str,
+        credentials: Option<&GitCredentials>,
+    ) -> Result<(), GitError> {
+        remote_command(repo_path, credentials)
+            .args(["push", remote, "--delete", branch_name])
+            .timeout(NETWORK_TIMEOUT)
+            .run()
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn diff(repo_path: &Path) -> Result<String, GitError> {
+        GitCommand::new(repo_path)
             .args(["diff", "HEAD"])
-            .output()
-            .map_err(|e| e.to_string())?;
+            .run()
+            .await
+    }
+
+    // `diff_args` selects what is compared, as for `diff_summary`.
+    pub async fn structured_diff(
+        worktree_path: &Path,
+        diff_args: &[&str],
+        options: &DiffOptions,
+    ) -> Result<StructuredDiff, GitError> {
+        let mut cmd = GitCommand::new(worktree_path);
+        cmd.args([
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            "--patch",
+            "--no-color",
+            "--no-ext-diff",
+        ])
+        .args(DIFF_PREFIX_ARGS);
 
-        if output.status.success() {
-            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        if options.detect_renames {
+            match options.rename_threshold {
+                Some(threshold) => cmd.arg(format!("--find-renames={}%", threshold)),
+                None => cmd.arg("--find-renames"),
+            };
         } else {
-            Err(String::from_utf8_lossy(&output.stderr).to_string())
+            cmd.arg("--no-renames");
+        }
+        if options.detect_copies {
+            cmd.arg("--find-copies");
+        }
+        match options.whitespace {
+            WhitespaceMode::Show => {}
+            WhitespaceMode::IgnoreAtEol => {
+                cmd.arg("--ignore-space-at-eol");
+            }
+            WhitespaceMode::IgnoreChange => {
+                cmd.arg("--ignore-space-change");
+            }
+            WhitespaceMode::IgnoreAll => {
+                cmd.arg("--ignore-all-space");
+            }
         }
+        if let Some(context) = options.context_lines {
+            cmd.arg(format!("--unified={}", context));
+        }
+
+        let stdout = cmd.args(diff_args).run().await?;
+
+        Ok(DiffParser::parse(&stdout))
     }
 
-    pub fn worktree_list_detailed(repo_path: &Path) -> Result<Vec<WorktreeInfo>, String> {
-        let output = Command::new("git")
-            .current_dir(repo_path)
-            .args(["worktree", "list", "--porcelain"])
-            .output()
-            .map_err(|e| e.to_string())?;
+    // Untracked, not ignored files under `paths` (all when empty), which `git diff` leaves
+    // out of every scope.
+    pub async fn untracked_files(
+        worktree_path: &Path,
+        paths: &[String],
+    ) -> Result<Vec<String>, GitError> {
+        let stdout = GitCommand::new(worktree_path)
+            .args([
+                "-c",
+                "core.quotePath=false",
+                "ls-files",
+                "--others",
+                "--exclude-standard",
+                "-z",
+                "--",
+            ])
+            .args(paths)
+            .run()
+            .await?;
 
-        if !output.status.success() {
-            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        Ok(stdout
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    // Patches adding the untracked files under `paths`, as `git diff` would show them once
+    // they were staged.
+    pub async fn untracked_patch(
+        worktree_path: &Path,
+        paths: &[String],
+        context_lines: Option<u32>,
+    ) -> Result<String, GitError> {
+        let mut patch = String::new();
+        for path in Self::untracked_files(worktree_path, paths).await? {
+            let mut cmd = GitCommand::new(worktree_path);
+            cmd.args([
+                "-c",
+                "core.quotePath=false",
+                "diff",
+                "--no-index",
+                "--patch",
+                "--no-color",
+                "--no-ext-diff",
+            ])
+            .args(DIFF_PREFIX_ARGS);
+            if let Some(context) = context_lines {
+                cmd.arg(format!("--unified={}", context));
+            }
+
+            // `--no-index` exits with 1 when the files differ, which they always do here.
+            let output = cmd.args(["--", "/dev/null", &path]).output().await?;
+            if output.code() != Some(1) {
+                return Err(output.into_error());
+            }
+            patch.push_str(&output.stdout_text());
+        }
+        Ok(patch)
+    }
+
+    pub async fn worktree_list_detailed(repo_path: &Path) -> Result<Vec<WorktreeInfo>, GitError> {
+        if let Some(worktrees) = read_in_process(repo_path, GitReader::worktrees).await {
+            return Ok(worktrees);
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = GitCommand::new(repo_path)
+            .args(["worktree", "list", "--porcelain"])
+            .run()
+            .await?;
+
         let mut worktrees = Vec::new();
         let mut current_path: Option<String> = None;
         let mut current_head: Option<String> = None;
@@ -140,91 +378,1045 @@ impl GitService {
         Ok(worktrees)
     }
 
-    pub fn branch_exists(repo_path: &Path, branch_name: &str) -> Result<bool, String> {
-        let output = Command::new("git")
-            .current_dir(repo_path)
+    pub async fn branch_exists(repo_path: &Path, branch_name: &str) -> Result<bool, GitError> {
+        let name = format!("refs/heads/{}", branch_name);
+        if let Some(exists) = read_in_process(repo_path, move |path| {
+            GitReader::reference_exists(path, &name)
+        })
+        .await
+        {
+            return Ok(exists);
+        }
+
+        let output = GitCommand::new(repo_path)
             .args([
                 "rev-parse",
                 "--verify",
                 &format!("refs/heads/{}", branch_name),
             ])
             .output()
-            .map_err(|e| e.to_string())?;
+            .await?;
 
-        Ok(output.status.success())
+        Ok(output.success())
     }
 
-    pub fn worktree_add_existing_branch(
+    pub async fn check_branch_name(repo_path: &Path, branch_name: &str) -> Result<(), GitError> {
+        let output = GitCommand::new(repo_path)
+            .args(["check-ref-format", "--branch", branch_name])
+            .output()
+            .await?;
+
+        if output.success() {
+            Ok(())
+        } else {
+            Err(GitError::Invalid(format!(
+                "Invalid branch name: {:?}",
+                branch_name
+            )))
+        }
+    }
+
+    pub async fn worktree_add_existing_branch(
         repo_path: &Path,
         worktree_path: &Path,
         branch_name: &str,
-    ) -> Result<(), String> {
-        let output = Command::new("git")
-            .current_dir(repo_path)
+    ) -> Result<(), GitError> {
+        GitCommand::new(repo_path)
             .args(["worktree", "add"])
             .arg(worktree_path)
             .arg(branch_name)
+            .timeout(WORKING_TREE_TIMEOUT)
+            .exclusive_repository()
+            .run()
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn current_branch(repo_path: &Path) -> Result<Option<String>, GitError> {
+        if let Some(branch) = read_in_process(repo_path, GitReader::current_branch).await {
+            return Ok(branch);
+        }
+
+        let output = GitCommand::new(repo_path)
+            .args(["symbolic-ref", "--quiet", "--short", "HEAD"])
             .output()
-            .map_err(|e| e.to_string())?;
+            .await?;
 
-        if output.status.success() {
-            Ok(())
+        // A detached HEAD makes symbolic-ref exit with 1 and print nothing.
+        if output.success() {
+            Ok(Some(output.stdout_text().trim().to_string()))
+        } else if output.code() == Some(1) {
+            Ok(None)
         } else {
-            Err(String::from_utf8_lossy(&output.stderr).to_string())
+            Err(output.into_error())
         }
     }
 
-    pub fn get_default_branch(repo_path: &Path) -> Result<String, String> {
-        let output = Command::new("git")
-            .current_dir(repo_path)
+    pub async fn get_default_branch(repo_path: &Path) -> Result<String, GitError> {
+        if let Some(branch) = read_in_process(repo_path, GitReader::default_branch).await {
+            return branch.ok_or_else(no_default_branch);
+        }
+
+        let output = GitCommand::new(repo_path)
             .args(["symbolic-ref", "refs/remotes/origin/HEAD", "--short"])
             .output()
-            .map_err(|e| e.to_string())?;
+            .await?;
 
-        if output.status.success() {
-            let branch = String::from_utf8_lossy(&output.stdout)
+        if output.success() {
+            let branch = output
+                .stdout_text()
                 .trim()
                 .trim_start_matches("origin/")
                 .to_string();
             return Ok(branch);
         }
 
-        if Self::branch_exists(repo_path, "main")? {
+        if Self::branch_exists(repo_path, "main").await? {
             return Ok("main".to_string());
         }
 
-        if Self::branch_exists(repo_path, "master")? {
+        if Self::branch_exists(repo_path, "master").await? {
             return Ok("master".to_string());
         }
 
-        Err("Cannot determine default branch: neither 'main' nor 'master' exists".to_string())
+        Err(no_default_branch())
     }
 
-    pub fn diff_with_base(worktree_path: &Path, base_branch: &str) -> Result<String, String> {
-        let merge_base_output = Command::new("git")
-            .current_dir(worktree_path)
-            .args(["merge-base", base_branch, "HEAD"])
-            .output()
-            .map_err(|e| e.to_string())?;
+    pub async fn merge_base(repo_path: &Path, a: &str, b: &str) -> Result<String, GitError> {
+        let stdout = GitCommand::new(repo_path)
+            .args(["merge-base", a, b])
+            .run()
+            .await?;
 
-        let base_commit = if merge_base_output.status.success() {
-            String::from_utf8_lossy(&merge_base_output.stdout)
-                .trim()
-                .to_string()
+        Ok(stdout.trim().to_string())
+    }
+
+    // Turns a scope into the revision arguments for `git diff`, optionally limited to paths.
+    pub async fn diff_scope_args(
+        worktree_path: &Path,
+        scope: &DiffScope,
+        base_ref: &str,
+        paths: &[String],
+    ) -> Result<Vec<String>, GitError> {
+        let mut args = match scope {
+            DiffScope::Base => vec![merge_base_or_ref(worktree_path, base_ref).await],
+            DiffScope::Uncommitted => vec!["HEAD".to_string()],
+            DiffScope::Staged => vec!["--cached".to_string()],
+            DiffScope::Unstaged => vec![],
+            DiffScope::Branch => vec![
+                merge_base_or_ref(worktree_path, base_ref).await,
+                "HEAD".to_string(),
+            ],
+            DiffScope::Commit { sha } => {
+                check_revision(sha)?;
+                let commit = Self::rev_parse(worktree_path, &format!("{}^{{commit}}", sha))
+                    .await
+                    .map_err(|_| GitError::Invalid(format!("Unknown commit: {}", sha)))?;
+                // A root commit is compared against the empty tree.
+                let parent = Self::rev_parse(worktree_path, &format!("{}^", commit))
+                    .await
+                    .unwrap_or_else(|_| EMPTY_TREE.to_string());
+                vec![parent, commit]
+            }
+            DiffScope::Range { from, to } => {
+                for reference in [from, to] {
+                    check_revision(reference)?;
+                    Self::rev_parse(worktree_path, &format!("{}^{{commit}}", reference))
+                        .await
+                        .map_err(|_| {
+                            GitError::Invalid(format!("Unknown revision: {}", reference))
+                        })?;
+                }
+                vec![from.clone(), to.clone()]
+            }
+        };
+
+        if !paths.is_empty() {
+            args.push("--".to_string());
+            args.extend(paths.iter().cloned());
+        }
+
+        Ok(args)
+    }
+
+    pub async fn diff_patch(worktree_path: &Path, diff_args: &[&str]) -> Result<String, GitError> {
+        GitCommand::new(worktree_path)
+            .args(["diff", "--no-color", "--no-ext-diff"])
+            .args(DIFF_PREFIX_ARGS)
+            .args(diff_args)
+            .run()
+            .await
+    }
+
+    pub async fn status(
+        worktree_path: &Path,
+        base_ref: Option<&str>,
+    ) -> Result<WorktreeGitStatus, GitError> {
+        let base = base_ref.map(str::to_string);
+        if let Some(status) = read_in_process(worktree_path, move |path| {
+            GitReader::status(path, base.as_deref())
+        })
+        .await
+        {
+            return Ok(status);
+        }
+
+        let stdout = GitCommand::new(worktree_path)
+            .args(["status", "--porcelain=v2", "--branch", "-z"])
+            .run()
+            .await?;
+
+        let mut status = parse_status_v2(&stdout);
+        status.path = worktree_path.to_string_lossy().to_string();
+        status.operation = Self::operation_in_progress(worktree_path).await?;
+
+        if let Some(base_ref) = base_ref {
+            let range = format!("{}...HEAD", base_ref);
+            let output = GitCommand::new(worktree_path)
+                .args(["rev-list", "--left-right", "--count", &range])
+                .output()
+                .await?;
+
+            // An unknown base ref only leaves the base counts empty.
+            if output.success() {
+                let counts = output.stdout_text();
+                let mut counts = counts.split_whitespace().map(|n| n.parse::<u32>().ok());
+                status.behind_base = counts.next().flatten();
+                status.ahead_base = counts.next().flatten();
+            }
+            status.base_ref = Some(base_ref.to_string());
+        }
+
+        Ok(status)
+    }
+
+    pub async fn git_dir(worktree_path: &Path) -> Result<PathBuf, GitError> {
+        if let Some(git_dir) = read_in_process(worktree_path, GitReader::git_dir).await {
+            return Ok(git_dir);
+        }
+
+        let stdout = GitCommand::new(worktree_path)
+            .args(["rev-parse", "--absolute-git-dir"])
+            .run()
+            .await?;
+
+        Ok(PathBuf::from(stdout.trim()))
+    }
+
+    pub async fn operation_in_progress(
+        worktree_path: &Path,
+    ) -> Result<Option<GitOperation>, GitError> {
+        let git_dir = Self::git_dir(worktree_path).await?;
+
+        let operation =
+            if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+                Some(GitOperation::Rebase)
+            } else if git_dir.join("MERGE_HEAD").exists() {
+                Some(GitOperation::Merge)
+            } else if git_dir.join("CHERRY_PICK_HEAD").exists() {
+                Some(GitOperation::CherryPick)
+            } else if git_dir.join("REVERT_HEAD").exists() {
+                Some(GitOperation::Revert)
+            } else {
+                None
+            };
+
+        Ok(operation)
+    }
+
+    // Returns Ok(false) when the operation stopped on conflicts; other failures are errors.
+    pub async fn sync(
+        worktree_path: &Path,
+        base_ref: &str,
+        strategy: SyncStrategy,
+    ) -> Result<bool, GitError> {
+        let mut cmd = non_interactive_command(worktree_path);
+        match strategy {
+            SyncStrategy::Rebase => cmd.args(["rebase", base_ref]),
+            SyncStrategy::Merge => cmd.args(["merge", "--no-edit", base_ref]),
+        };
+        let output = cmd.output().await?;
+
+        Self::conflict_outcome(worktree_path, output).await
+    }
+
+    pub async fn continue_operation(
+        worktree_path: &Path,
+        operation: &GitOperation,
+    ) -> Result<bool, GitError> {
+        let mut cmd = non_interactive_command(worktree_path);
+        match operation {
+            GitOperation::Rebase => cmd.args(["rebase", "--continue"]),
+            GitOperation::Merge => cmd.args(["commit", "--no-edit"]),
+            GitOperation::CherryPick => cmd.args(["cherry-pick", "--continue"]),
+            GitOperation::Revert => cmd.args(["revert", "--continue"]),
+        };
+        let output = cmd.output().await?;
+
+        Self::conflict_outcome(worktree_path, output).await
+    }
+
+    pub async fn abort_operation(
+        worktree_path: &Path,
+        operation: &GitOperation,
+    ) -> Result<(), GitError> {
+        let subcommand = match operation {
+            GitOperation::Rebase => "rebase",
+            GitOperation::Merge => "merge",
+            GitOperation::CherryPick => "cherry-pick",
+            GitOperation::Revert => "revert",
+        };
+        non_interactive_command(worktree_path)
+            .args([subcommand, "--abort"])
+            .run()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn conflict_outcome(worktree_path: &Path, output: GitOutput) -> Result<bool, GitError> {
+        if output.success() {
+            return Ok(true);
+        }
+        if Self::operation_in_progress(worktree_path).await?.is_some()
+            && !Self::conflicts(worktree_path).await?.is_empty()
+        {
+            return Ok(false);
+        }
+
+        // Some failures (e.g. "nothing to commit") are only reported on stdout.
+        let mut output = output;
+        if output.stderr.trim().is_empty() {
+            output.stderr = output.stdout_text();
+        }
+        Err(output.into_error())
+    }
+
+    pub async fn conflicts(worktree_path: &Path) -> Result<Vec<ConflictFile>, GitError> {
+        let stdout = GitCommand::new(worktree_path)
+            .args(["ls-files", "--unmerged", "-z"])
+            .run()
+            .await?;
+
+        // Each entry is "<mode> <blob> <stage>\t<path>".
+        let mut conflicts: Vec<ConflictFile> = Vec::new();
+        for entry in stdout.split('\0').filter(|e| !e.is_empty()) {
+            let Some((info, path)) = entry.split_once('\t') else {
+                continue;
+            };
+            let mut fields = info.split(' ');
+            let blob = fields.nth(1).map(str::to_string);
+            let stage = fields.next().unwrap_or_default();
+
+            let index = match conflicts.iter().position(|c| c.path == path) {
+                Some(index) => index,
+                None => {
+                    conflicts.push(ConflictFile {
+                        path: path.to_string(),
+                        ancestor_blob: None,
+                        ours_blob: None,
+                        theirs_blob: None,
+                        regions: conflict_regions(&worktree_path.join(path)),
+                    });
+                    conflicts.len() - 1
+                }
+            };
+            let conflict = &mut conflicts[index];
+            match stage {
+                "1" => conflict.ancestor_blob = blob,
+                "2" => conflict.ours_blob = blob,
+                "3" => conflict.theirs_blob = blob,
+                _ => {}
+            }
+        }
+
+        Ok(conflicts)
+    }
+
+    pub async fn stage_paths(worktree_path: &Path, paths: &[String]) -> Result<(), GitError> {
+        GitCommand::new(worktree_path)
+            .args(["add", "--"])
+            .args(paths)
+            .exclusive()
+            .run()
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn unstage_paths(worktree_path: &Path, paths: &[String]) -> Result<(), GitError> {
+        GitCommand::new(worktree_path)
+            .args(["restore", "--staged", "--"])
+            .args(paths)
+            .exclusive()
+            .run()
+            .await?;
+
+        Ok(())
+    }
+
+    // Raw bytes: the patch is applied back as-is, whatever the file's encoding.
+    pub async fn file_diff(
+        worktree_path: &Path,
+        path: &str,
+        cached: bool,
+    ) -> Result<Vec<u8>, GitError> {
+        let mut cmd = GitCommand::new(worktree_path);
+        cmd.args(["diff", "--no-color", "--no-ext-diff"])
+            .args(DIFF_PREFIX_ARGS);
+        if cached {
+            cmd.arg("--cached");
+        }
+
+        let output = cmd.args(["--", path]).output().await?;
+        if output.success() {
+            Ok(output.stdout)
         } else {
-            base_branch.to_string()
+            Err(output.into_error())
+        }
+    }
+
+    pub async fn apply_to_index(
+        worktree_path: &Path,
+        patch: &[u8],
+        reverse: bool,
+    ) -> Result<(), GitError> {
+        let mut cmd = GitCommand::new(worktree_path);
+        cmd.args(["apply", "--cached"]);
+        if reverse {
+            cmd.arg("--reverse");
+        }
+        cmd.arg("-").stdin(patch).exclusive().run().await?;
+
+        Ok(())
+    }
+
+    // `diff_args` selects what is compared, e.g. `["--cached"]` or `["main...HEAD"]`.
+    pub async fn diff_summary(
+        worktree_path: &Path,
+        diff_args: &[&str],
+    ) -> Result<Vec<FileChangeSummary>, GitError> {
+        if diff_args == ["--cached"] {
+            if let Some(changes) = read_in_process(worktree_path, GitReader::staged_changes).await {
+                return Ok(changes);
+            }
+        }
+
+        let run = |format: &'static str| async move {
+            GitCommand::new(worktree_path)
+                .args(["diff", "-M", "-z", format])
+                .args(diff_args)
+                .run()
+                .await
         };
 
-        let output = Command::new("git")
-            .current_dir(worktree_path)
-            .args(["diff", &base_commit])
+        let name_status = run("--name-status").await?;
+        let numstat = run("--numstat").await?;
+
+        let mut changes = Vec::new();
+        let mut fields = name_status.split('\0').filter(|f| !f.is_empty());
+        while let Some(code) = fields.next() {
+            let status = match code.chars().next() {
+                Some('A') => FileChangeStatus::Added,
+                Some('M') => FileChangeStatus::Modified,
+                Some('D') => FileChangeStatus::Deleted,
+                Some('R') => FileChangeStatus::Renamed,
+                Some('C') => FileChangeStatus::Copied,
+                Some('T') => FileChangeStatus::TypeChanged,
+                _ => FileChangeStatus::Unknown,
+            };
+            let old_path = match status {
+                FileChangeStatus::Renamed | FileChangeStatus::Copied => {
+                    fields.next().map(str::to_string)
+                }
+                _ => None,
+            };
+            let Some(path) = fields.next() else {
+                break;
+            };
+            changes.push(FileChangeSummary {
+                path: path.to_string(),
+                old_path,
+                status,
+                additions: None,
+                deletions: None,
+            });
+        }
+
+        // numstat lists the same files in the same order; renames are written as
+        // "<added>\t<deleted>\t\0<old>\0<new>" and binary files as "-\t-".
+        let mut fields = numstat.split('\0');
+        for change in changes.iter_mut() {
+            let Some(counts) = fields.next() else {
+                break;
+            };
+            let mut counts = counts.split('\t');
+            change.additions = counts.next().and_then(|n| n.parse().ok());
+            change.deletions = counts.next().and_then(|n| n.parse().ok());
+            if counts.next().is_some_and(|path| path.is_empty()) {
+                fields.next();
+                fields.next();
+            }
+        }
+
+        Ok(changes)
+    }
+
+    pub async fn commit(worktree_path: &Path, message: &str) -> Result<String, GitError> {
+        non_interactive_command(worktree_path)
+            .args(["commit", "--cleanup=strip", "-F", "-"])
+            .stdin(message)
+            .timeout(WORKING_TREE_TIMEOUT)
+            .exclusive()
+            .run()
+            .await?;
+
+        Self::rev_parse(worktree_path, "HEAD").await
+    }
+
+    // Commits in `revision_range` (e.g. "<base>..HEAD"), newest first, with per-commit stats.
+    pub async fn log(
+        repo_path: &Path,
+        revision_range: &str,
+        max_count: Option<usize>,
+    ) -> Result<Vec<CommitInfo>, GitError> {
+        check_revision(revision_range)?;
+        let mut cmd = GitCommand::new(repo_path);
+        cmd.args([
+            "-c",
+            "core.quotePath=false",
+            "log",
+            "--no-color",
+            "--numstat",
+            "--format=%x1e%H%x1f%h%x1f%P%x1f%an%x1f%ae%x1f%aI%x1f%s%x1f%b%x1f",
+        ]);
+        if let Some(max_count) = max_count {
+            cmd.arg(format!("--max-count={}", max_count));
+        }
+        let stdout = cmd.args([revision_range, "--"]).run().await?;
+
+        Ok(stdout.split('\x1e').filter_map(parse_log_record).collect())
+    }
+
+    pub async fn has_uncommitted_changes(worktree_path: &Path) -> Result<bool, GitError> {
+        let stdout = GitCommand::new(worktree_path)
+            .args(["status", "--porcelain", "--untracked-files=no"])
+            .run()
+            .await?;
+
+        Ok(!stdout.is_empty())
+    }
+
+    // Returns Ok(false) when the revert stopped on conflicts. Merge commits are reverted
+    // against their first parent.
+    pub async fn revert(worktree_path: &Path, sha: &str) -> Result<bool, GitError> {
+        check_revision(sha)?;
+        let is_merge = Self::rev_parse(worktree_path, &format!("{}^2", sha))
+            .await
+            .is_ok();
+
+        let mut cmd = non_interactive_command(worktree_path);
+        cmd.args(["revert", "--no-edit"]);
+        if is_merge {
+            cmd.args(["-m", "1"]);
+        }
+        let output = cmd.arg(sha).output().await?;
+
+        Self::conflict_outcome(worktree_path, output).await
+    }
+
+    pub async fn reset(worktree_path: &Path, sha: &str, mode: ResetMode) -> Result<(), GitError> {
+        check_revision(sha)?;
+        let mode = match mode {
+            ResetMode::Soft => "--soft",
+            ResetMode::Mixed => "--mixed",
+            ResetMode::Hard => "--hard",
+        };
+        non_interactive_command(worktree_path)
+            .args(["reset", "--quiet", mode, sha, "--"])
+            .run()
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn rev_parse(repo_path: &Path, reference: &str) -> Result<String, GitError> {
+        let revision = reference.to_string();
+        if let Some(sha) =
+            read_in_process(repo_path, move |path| GitReader::rev_parse(path, &revision)).await
+        {
+            return sha
+                .ok_or_else(|| GitError::Invalid(format!("Unknown revision: {}", reference)));
+        }
+
+        let stdout = GitCommand::new(repo_path)
+            .args(["rev-parse", "--verify", reference])
+            .run()
+            .await?;
+
+        Ok(stdout.trim().to_string())
+    }
+
+    // Size of `path` at `revision`, or `None` when the file does not exist there.
+    pub async fn blob_size(
+        repo_path: &Path,
+        revision: &str,
+        path: &str,
+    ) -> Result<Option<u64>, GitError> {
+        check_revision(revision)?;
+        let output = GitCommand::new(repo_path)
+            .args(["cat-file", "-s", &format!("{}:{}", revision, path)])
             .output()
-            .map_err(|e| e.to_string())?;
+            .await?;
 
-        if output.status.success() {
-            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        if !output.success() {
+            return Ok(None);
+        }
+
+        output
+            .stdout_text()
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|e| GitError::Unexpected(format!("Unexpected cat-file output: {}", e)))
+    }
+
+    pub async fn read_blob(
+        repo_path: &Path,
+        revision: &str,
+        path: &str,
+    ) -> Result<Vec<u8>, GitError> {
+        check_revision(revision)?;
+        let output = GitCommand::new(repo_path)
+            .args(["cat-file", "blob", &format!("{}:{}", revision, path)])
+            .output()
+            .await?;
+
+        if output.success() {
+            Ok(output.stdout)
+        } else {
+            Err(output.into_error())
+        }
+    }
+
+    pub async fn remotes(repo_path: &Path) -> Result<Vec<(String, String)>, GitError> {
+        let output = GitCommand::new(repo_path)
+            .args(["config", "--get-regexp", r"^remote\..*\.url$"])
+            .output()
+            .await?;
+
+        // Exit code 1 means no remotes are configured.
+        if !output.success() && output.code() != Some(1) {
+            return Err(output.into_error());
+        }
+
+        Ok(output
+            .stdout_text()
+            .lines()
+            .filter_map(|line| {
+                let (key, url) = line.split_once(' ')?;
+                let name = key.strip_prefix("remote.")?.strip_suffix(".url")?;
+                Some((name.to_string(), url.trim().to_string()))
+            })
+            .collect())
+    }
+
+    pub async fn clone(
+        credentials: &GitCredentials,
+        url: &str,
+        destination: &Path,
+        options: &CloneOptions,
+        on_progress: impl FnMut(&str, Option<u8>, &str) + Send,
+    ) -> Result<(), GitError> {
+        let parent = destination
+            .parent()
+            .ok_or_else(|| GitError::Invalid("Cannot determine parent directory".to_string()))?;
+
+        let mut cmd = GitCommand::from_std(credentials.command(parent), parent);
+        cmd.args(["clone", "--progress"]);
+        if let Some(depth) = options.depth {
+            cmd.arg(format!("--depth={}", depth));
+        }
+        if let Some(ref filter) = options.filter {
+            cmd.arg(format!("--filter={}", filter));
+        }
+        if options.single_branch {
+            cmd.arg("--single-branch");
+        }
+        if let Some(ref branch) = options.branch {
+            cmd.args(["--branch", branch]);
+        }
+        cmd.arg("--")
+            .arg(url)
+            .arg(destination)
+            .timeout(CLONE_TIMEOUT);
+
+        let output = run_with_progress(cmd, on_progress).await?;
+        if output.success {
+            Ok(())
         } else {
-            Err(String::from_utf8_lossy(&output.stderr).to_string())
+            Err(GitError::Failed {
+                command: "clone".to_string(),
+                code: output.code,
+                stderr: output.messages.join("\n"),
+            })
+        }
+    }
+
+    // Pushes HEAD to `remote_branch` and records it as the upstream. Rejections are not
+    // errors: they are reported through the porcelain status lines in the output.
+    pub async fn push(
+        worktree_path: &Path,
+        credentials: Option<&GitCredentials>,
+        remote: &str,
+        remote_branch: &str,
+        force_with_lease: bool,
+        on_progress: impl FnMut(&str, Option<u8>, &str) + Send,
+    ) -> Result<ProgressOutput, GitError> {
+        let mut cmd = remote_command(worktree_path, credentials);
+        cmd.args(["push", "--progress", "--porcelain", "--set-upstream"]);
+        if force_with_lease {
+            cmd.arg("--force-with-lease");
+        }
+        cmd.arg(remote)
+            .arg(format!("HEAD:refs/heads/{}", remote_branch))
+            .timeout(NETWORK_TIMEOUT);
+
+        run_with_progress(cmd, on_progress).await
+    }
+}
+
+pub struct ProgressOutput {
+    pub success: bool,
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub messages: Vec<String>,
+}
+
+// Runs a git command that reports `--progress` on stderr. Progress lines are passed to
+// `on_progress`; every other stderr line is collected as a message.
+async fn run_with_progress(
+    mut cmd: GitCommand,
+    mut on_progress: impl FnMut(&str, Option<u8>, &str) + Send,
+) -> Result<ProgressOutput, GitError> {
+    let mut messages = Vec::new();
+    let output = cmd
+        .output_with_stderr_lines(|line| match parse_progress_line(line) {
+            Some((phase, percent)) => on_progress(phase, percent, line),
+            None => messages.push(line.to_string()),
+        })
+        .await?;
+
+    Ok(ProgressOutput {
+        success: output.success(),
+        code: output.code(),
+        stdout: output.stdout_text(),
+        messages,
+    })
+}
+
+fn parse_progress_line(line: &str) -> Option<(&str, Option<u8>)> {
+    let line = line.strip_prefix("remote: ").unwrap_or(line);
+    let (phase, rest) = line.split_once(": ")?;
+    let rest = rest.trim_start();
+    if phase.is_empty() || !phase.chars().all(|c| c.is_alphabetic() || c == ' ') {
+        return None;
+    }
+
+    // Progress reports start with a count ("Enumerating objects: 5, done.") or a
+    // percentage; anything else (e.g. "fatal: ...") is a message.
+    if !rest.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let percent = rest
+        .split_once('%')
+        .and_then(|(value, _)| value.trim().parse::<u8>().ok());
+
+    Some((phase, percent))
+}
+
+// Splits a remote URL into (host, owner, name). Handles https, ssh:// and scp-style
+// (`git@host:owner/name.git`) URLs.
+pub fn parse_remote_url(url: &str) -> Option<(String, String, String)> {
+    let (host, path) = if let Some((_, rest)) = url.split_once("://") {
+        let rest = rest.rsplit_once('@').map_or(rest, |(_, r)| r);
+        rest.split_once('/')?
+    } else {
+        let rest = url.rsplit_once('@').map_or(url, |(_, r)| r);
+        rest.split_once(':')?
+    };
+
+    let host = host.split(':').next()?.to_ascii_lowercase();
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, name) = path.split_once('/')?;
+
+    if host.is_empty() || owner.is_empty() || name.is_empty() || name.contains('/') {
+        return None;
+    }
+
+    Some((host, owner.to_string(), name.to_string()))
+}
+
+// One `git log` record: the \x1f-separated format fields, with the body and the
+// trailing field (the --numstat lines) last.
+fn parse_log_record(record: &str) -> Option<CommitInfo> {
+    let fields: Vec<&str> = record.splitn(9, '\x1f').collect();
+    let [sha, short_sha, parents, author_name, author_email, authored_at, subject, body, numstat] =
+        fields.as_slice()
+    else {
+        return None;
+    };
+
+    let mut stats = DiffStats::default();
+    for line in numstat.lines().filter(|line| !line.trim().is_empty()) {
+        let mut parts = line.split('\t');
+        let (Some(added), Some(deleted)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        stats.files_changed += 1;
+        // Binary files report "-" for both counts.
+        stats.additions += added.parse::<u32>().unwrap_or(0);
+        stats.deletions += deleted.parse::<u32>().unwrap_or(0);
+    }
+
+    Some(CommitInfo {
+        sha: sha.to_string(),
+        short_sha: short_sha.to_string(),
+        parents: parents.split_whitespace().map(str::to_string).collect(),
+        author_name: author_name.to_string(),
+        author_email: author_email.to_string(),
+        authored_at: authored_at.to_string(),
+        subject: subject.to_string(),
+        body: body.trim().to_string(),
+        stats,
+    })
+}
+
+// Revisions come from the frontend and must never be mistaken for options.
+fn check_revision(revision: &str) -> Result<(), GitError> {
+    if revision.is_empty() || revision.starts_with('-') {
+        return Err(GitError::Invalid(format!(
+            "Invalid revision: {:?}",
+            revision
+        )));
+    }
+    Ok(())
+}
+
+// Runs `read` with libgit2 on a blocking thread. `None` leaves the answer to the CLI: the
+// in-process backend is switched off or libgit2 could not read the repository.
+async fn read_in_process<T, F>(path: &Path, read: F) -> Option<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&Path) -> Result<T, git2::Error> + Send + 'static,
+{
+    if GitReader::backend() == GitReadBackend::Cli {
+        return None;
+    }
+
+    let path = path.to_path_buf();
+    match tokio::task::spawn_blocking(move || read(&path)).await {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(e)) => {
+            eprintln!("In-process git read failed, using the git CLI: {}", e);
+            None
         }
+        Err(e) => {
+            eprintln!("In-process git read panicked, using the git CLI: {}", e);
+            None
+        }
+    }
+}
+
+fn no_default_branch() -> GitError {
+    GitError::Unexpected(
+        "Cannot determine default branch: neither 'main' nor 'master' exists".to_string(),
+    )
+}
+
+async fn merge_base_or_ref(worktree_path: &Path, base_ref: &str) -> String {
+    GitService::merge_base(worktree_path, base_ref, "HEAD")
+        .await
+        .unwrap_or_else(|_| base_ref.to_string())
+}
+
+// History-rewriting commands must never open an editor the app cannot show.
+fn non_interactive_command(worktree_path: &Path) -> GitCommand {
+    let mut cmd = GitCommand::new(worktree_path);
+    cmd.env("GIT_EDITOR", "true")
+        .timeout(WORKING_TREE_TIMEOUT)
+        .exclusive();
+    cmd
+}
+
+fn conflict_regions(path: &Path) -> Vec<ConflictRegion> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return vec![];
+    };
+
+    let mut regions = Vec::new();
+    let mut open: Option<(u32, Option<u32>)> = None;
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index as u32 + 1;
+        if line.starts_with("<<<<<<<") {
+            open = Some((line_number, None));
+        } else if line.starts_with("=======") {
+            if let Some((_, ref mut separator)) = open {
+                *separator = Some(line_number);
+            }
+        } else if line.starts_with(">>>>>>>") {
+            if let Some((start_line, separator_line)) = open.take() {
+                regions.push(ConflictRegion {
+                    start_line,
+                    separator_line,
+                    end_line: line_number,
+                });
+            }
+        }
+    }
+
+    regions
+}
+
+// Commands that talk to a remote never prompt; with credentials the token is supplied
+// through askpass.
+fn remote_command(repo_path: &Path, credentials: Option<&GitCredentials>) -> GitCommand {
+    match credentials {
+        Some(credentials) => GitCommand::from_std(credentials.command(repo_path), repo_path),
+        None => GitCommand::new(repo_path),
+    }
+}
+
+// Parses `git status --porcelain=v2 --branch -z` output.
+fn parse_status_v2(output: &str) -> WorktreeGitStatus {
+    let mut status = WorktreeGitStatus::default();
+    let mut entries = output.split('\0');
+
+    while let Some(entry) = entries.next() {
+        if let Some(header) = entry.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.oid" if value != "(initial)" => status.head = Some(value.to_string()),
+                "branch.head" if value != "(detached)" => status.branch = Some(value.to_string()),
+                "branch.upstream" => status.upstream = Some(value.to_string()),
+                "branch.ab" => {
+                    for count in value.split_whitespace() {
+                        if let Some(ahead) = count.strip_prefix('+') {
+                            status.ahead_upstream = ahead.parse().ok();
+                        } else if let Some(behind) = count.strip_prefix('-') {
+                            status.behind_upstream = behind.parse().ok();
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        let mut fields = entry.splitn(2, ' ');
+        let kind = fields.next().unwrap_or_default();
+        let rest = fields.next().unwrap_or_default();
+
+        match kind {
+            "1" | "2" => {
+                let xy = rest.as_bytes();
+                if xy.first().is_some_and(|&x| x != b'.') {
+                    status.staged += 1;
+                }
+                if xy.get(1).is_some_and(|&y| y != b'.') {
+                    status.unstaged += 1;
+                }
+                // Renames and copies are followed by a separate entry holding the source path.
+                if kind == "2" {
+                    entries.next();
+                }
+            }
+            "u" => {
+                // u <XY> <sub> <m1> <m2> <m3> <mW> <h1> <h2> <h3> <path>
+                if let Some(path) = rest.splitn(10, ' ').nth(9) {
+                    status.conflicted.push(path.to_string());
+                }
+            }
+            "?" => status.untracked += 1,
+            _ => {}
+        }
+    }
+
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_status_entries() {
+        let output = [
+            "# branch.oid 6fccd998fd98a289537ebc247ae9f5f3d88c8bd5",
+            "# branch.head main",
+            "# branch.upstream origin/main",
+            "# branch.ab +2 -1",
+            "1 .M N... 100644 100644 100644 d905d9da82c97264ab6f4920e20242e088850ce9 d905d9da82c97264ab6f4920e20242e088850ce9 edited.txt",
+            "2 R. N... 100644 100644 100644 28ce6a8b26aa170e1de65536fe8abe1832bd3242 28ce6a8b26aa170e1de65536fe8abe1832bd3242 R100 re named.txt",
+            "moved.txt",
+            "u UU N... 100644 100644 100644 100644 587be6b4c3f93f93c489c0111bba5596147a26cb ba2906d0666cf726c7eaadd2cd3db615dedfdf3a 2299c37978265a95cbe835a4b0f0bbf15aad5549 con flict.txt",
+            "? untracked.txt",
+            "",
+        ]
+        .join("\0");
+
+        let status = parse_status_v2(&output);
+        assert_eq!(
+            status.head.as_deref(),
+            Some("6fccd998fd98a289537ebc247ae9f5f3d88c8bd5")
+        );
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!(
+            (status.ahead_upstream, status.behind_upstream),
+            (Some(2), Some(1))
+        );
+        // The rename's source path entry is not counted as an entry of its own.
+        assert_eq!(
+            (status.staged, status.unstaged, status.untracked),
+            (1, 1, 1)
+        );
+        assert_eq!(status.conflicted, ["con flict.txt"]);
+    }
+
+    #[test]
+    fn parses_status_of_a_new_detached_repository() {
+        let status = parse_status_v2("# branch.oid (initial)\0# branch.head (detached)\0");
+        assert_eq!(status.head, None);
+        assert_eq!(status.branch, None);
+        assert_eq!(status.upstream, None);
+        assert_eq!(status.ahead_upstream, None);
+    }
+
+    #[test]
+    fn parses_remote_urls() {
+        let expected = Some((
+            "github.com".to_string(),
+            "owner".to_string(),
+            "repo".to_string(),
+        ));
+        for url in [
+            "https://github.com/owner/repo.git",
+            "https://user@GitHub.com/owner/repo/",
+            "ssh://git@github.com:22/owner/repo.git",
+            "git@github.com:owner/repo.git",
+        ] {
+            assert_eq!(parse_remote_url(url), expected, "{}", url);
+        }
+
+        assert_eq!(parse_remote_url("/srv/git/repo.git"), None);
+        assert_eq!(parse_remote_url("https://github.com/owner"), None);
+        assert_eq!(
+            parse_remote_url("https://github.com/owner/group/repo"),
+            None
+        );
     }
 }
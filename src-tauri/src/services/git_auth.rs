@@ -0,0 +1,73 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use uuid::Uuid;
+
+const TOKEN_ENV: &str = "MARIONETTE_GIT_TOKEN";
+
+#[cfg(unix)]
+const ASKPASS_FILE: &str = "askpass.sh";
+#[cfg(unix)]
+const ASKPASS_SCRIPT: &str = "#!/bin/sh\n\
+case \"$1\" in\n\
+  Username*) echo x-access-token ;;\n\
+  *) echo \"$MARIONETTE_GIT_TOKEN\" ;;\n\
+esac\n";
+
+#[cfg(windows)]
+const ASKPASS_FILE: &str = "askpass.bat";
+#[cfg(windows)]
+const ASKPASS_SCRIPT: &str = "@echo off\r\n\
+echo %1 | findstr /b /c:\"\\\"Username\" >nul && (echo x-access-token) || (echo %MARIONETTE_GIT_TOKEN%)\r\n";
+
+// Hands a token to git through a throwaway GIT_ASKPASS script so it never appears in
+// remote URLs, `.git/config`, or process arguments. Stored credential helpers are
+// disabled for the command so the token is not persisted by them either.
+pub struct GitCredentials {
+    dir: PathBuf,
+    token: String,
+}
+
+impl GitCredentials {
+    pub fn new(token: String) -> Result<Self, String> {
+        let dir = std::env::temp_dir().join(format!("marionette-askpass-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create askpass directory: {}", e))?;
+
+        let credentials = Self { dir, token };
+        let script = credentials.script_path();
+        fs::write(&script, ASKPASS_SCRIPT)
+            .map_err(|e| format!("Failed to write askpass script: {}", e))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&script, fs::Permissions::from_mode(0o700))
+                .map_err(|e| format!("Failed to set askpass permissions: {}", e))?;
+        }
+
+        Ok(credentials)
+    }
+
+    fn script_path(&self) -> PathBuf {
+        self.dir.join(ASKPASS_FILE)
+    }
+
+    pub fn command(&self, current_dir: &Path) -> Command {
+        let mut cmd = Command::new("git");
+        cmd.current_dir(current_dir)
+            .env("GIT_ASKPASS", self.script_path())
+            .env(TOKEN_ENV, &self.token)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .args(["-c", "credential.helper="]);
+        cmd
+    }
+}
+
+impl Drop for GitCredentials {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            eprintln!("Failed to remove askpass directory {:?}: {}", self.dir, e);
+        }
+    }
+}
@@ -0,0 +1,520 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, Command};
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::CancellationToken;
+
+pub const DEFAULT_GIT_TIMEOUT: Duration = Duration::from_secs(120);
+
+// Readers of a worktree share these slots; commands that write its index take all of them.
+// Commands that manage worktrees or delete branches take all slots of the repository,
+// which worktree writers share.
+const MAX_GIT_PROCESSES_PER_REPOSITORY: u32 = 4;
+
+// How long a timed-out or cancelled git gets to exit after SIGTERM before it is killed.
+const TERMINATE_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+static SLOTS: Lazy<Mutex<HashMap<PathBuf, Arc<Semaphore>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// Running operations by id, each with the generation of the scope that registered it.
+// Locked only briefly and from `Drop`, hence a std mutex.
+static OPERATIONS: Lazy<std::sync::Mutex<HashMap<String, (u64, CancellationToken)>>> =
+    Lazy::new(|| std::sync::Mutex::new(HashMap::new()));
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+tokio::task_local! {
+    static CANCELLATION: CancellationToken;
+}
+
+#[derive(Debug, Error)]
+pub enum GitError {
+    #[error("Failed to run git {command}: {source}")]
+    Spawn {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("git {command} failed: {}", failure_message(.code, .stderr))]
+    Failed {
+        command: String,
+        code: Option<i32>,
+        stderr: String,
+    },
+    #[error("git {command} timed out after {} seconds", .timeout.as_secs())]
+    TimedOut { command: String, timeout: Duration },
+    #[error("git {command} was cancelled")]
+    Cancelled { command: String },
+    #[error("{0}")]
+    Invalid(String),
+    #[error("{0}")]
+    Unexpected(String),
+}
+
+impl From<GitError> for String {
+    fn from(error: GitError) -> Self {
+        error.to_string()
+    }
+}
+
+fn failure_message(code: &Option<i32>, stderr: &str) -> String {
+    match (stderr.trim(), code) {
+        ("", Some(code)) => format!("exit code {}", code),
+        ("", None) => "terminated by a signal".to_string(),
+        (stderr, _) => stderr.to_string(),
+    }
+}
+
+pub struct GitOutput {
+    pub command: String,
+    pub status: ExitStatus,
+    pub stdout: Vec<u8>,
+    pub stderr: String,
+}
+
+impl GitOutput {
+    pub fn success(&self) -> bool {
+        self.status.success()
+    }
+
+    pub fn code(&self) -> Option<i32> {
+        self.status.code()
+    }
+
+    pub fn stdout_text(&self) -> String {
+        String::from_utf8_lossy(&self.stdout).to_string()
+    }
+
+    pub fn into_error(self) -> GitError {
+        GitError::Failed {
+            command: self.command,
+            code: self.status.code(),
+            stderr: self.stderr,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LockScope {
+    Shared,
+    Worktree,
+    Repository,
+}
+
+// A git invocation run on the tokio runtime. Every command is bounded by a timeout,
+// observes the cancellation token of the operation it runs in (see `GitCancellation`)
+// and takes a slot in its worktree so concurrent commands do not fight over
+// `index.lock`.
+pub struct GitCommand {
+    command: Command,
+    repository: PathBuf,
+    stdin: Option<Vec<u8>>,
+    timeout: Duration,
+    lock: LockScope,
+}
+
+impl GitCommand {
+    pub fn new(current_dir: &Path) -> Self {
+        Self::from_std(std::process::Command::new("git"), current_dir)
+    }
+
+    // Adopts a command prepared elsewhere, e.g. by `GitCredentials`.
+    pub fn from_std(mut command: std::process::Command, current_dir: &Path) -> Self {
+        command
+            .current_dir(current_dir)
+            .env("GIT_TERMINAL_PROMPT", "0");
+        let mut command = Command::from(command);
+        command.kill_on_drop(true);
+
+        Self {
+            command,
+            repository: current_dir.to_path_buf(),
+            stdin: None,
+            timeout: DEFAULT_GIT_TIMEOUT,
+            lock: LockScope::Shared,
+        }
+    }
+
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.command.arg(arg);
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.command.args(args);
+        self
+    }
+
+    pub fn env(&mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> &mut Self {
+        self.command.env(key, value);
+        self
+    }
+
+    pub fn stdin(&mut self, input: impl Into<Vec<u8>>) -> &mut Self {
+        self.stdin = Some(input.into());
+        self
+    }
+
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
+    // For commands that write the index of their worktree (and the refs only it has
+    // checked out): they wait until no other git process runs in that worktree.
+    pub fn exclusive(&mut self) -> &mut Self {
+        self.lock = LockScope::Worktree;
+        self
+    }
+
+    // For commands that add, remove or prune worktrees or delete branches: they wait
+    // until no writer runs in any worktree of the repository. Readers are not blocked.
+    pub fn exclusive_repository(&mut self) -> &mut Self {
+        self.lock = LockScope::Repository;
+        self
+    }
+
+    // Runs the command whatever its exit status; only failing to run it is an error.
+    pub async fn output(&mut self) -> Result<GitOutput, GitError> {
+        self.execute(None).await
+    }
+
+    // Runs the command and returns its stdout, treating a non-zero exit as an error.
+    pub async fn run(&mut self) -> Result<String, GitError> {
+        let output = self.output().await?;
+        if output.success() {
+            Ok(output.stdout_text())
+        } else {
+            Err(output.into_error())
+        }
+    }
+
+    // Like `output`, but hands each stderr line to `on_line` as it arrives instead of
+    // collecting it. git rewrites progress lines in place with '\r', which also ends a line.
+    pub async fn output_with_stderr_lines(
+        &mut self,
+        mut on_line: impl FnMut(&str) + Send,
+    ) -> Result<GitOutput, GitError> {
+        self.execute(Some(&mut on_line)).await
+    }
+
+    async fn execute(
+        &mut self,
+        on_stderr_line: Option<&mut (dyn FnMut(&str) + Send)>,
+    ) -> Result<GitOutput, GitError> {
+        let command = self.label();
+        let timeout = self.timeout;
+        let cancellation = CANCELLATION
+            .try_with(CancellationToken::clone)
+            .unwrap_or_default();
+
+        // Readers must not take optional locks (e.g. `git status` refreshing the index),
+        // or they would race the exclusive writers.
+        if self.lock == LockScope::Shared {
+            self.command.env("GIT_OPTIONAL_LOCKS", "0");
+        }
+
+        let _slots = tokio::select! {
+            slots = acquire_slots(&self.repository, self.lock) => slots,
+            _ = cancellation.cancelled() => return Err(GitError::Cancelled { command }),
+        };
+
+        let stdin = self.stdin.take();
+        let mut child = self
+            .command
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|source| GitError::Spawn {
+                command: command.clone(),
+                source,
+            })?;
+
+        let child_stdin = child.stdin.take();
+        let child_stdout = child.stdout.take();
+        let child_stderr = child.stderr.take();
+
+        let run = async {
+            let write = async {
+                if let (Some(mut pipe), Some(input)) = (child_stdin, stdin) {
+                    // git may exit without reading all of it; that shows in its exit status.
+                    let _ = pipe.write_all(&input).await;
+                }
+            };
+            let (_, stdout, stderr, status) = tokio::join!(
+                write,
+                read_to_end(child_stdout),
+                read_stderr(child_stderr, on_stderr_line),
+                child.wait(),
+            );
+            status.map(|status| (status, stdout, stderr))
+        };
+
+        let interrupted = tokio::select! {
+            result = run => Ok(result),
+            _ = tokio::time::sleep(timeout) => Err(GitError::TimedOut {
+                command: command.clone(),
+                timeout,
+            }),
+            _ = cancellation.cancelled() => Err(GitError::Cancelled {
+                command: command.clone(),
+            }),
+        };
+        let (status, stdout, stderr) = match interrupted {
+            Ok(result) => result.map_err(|source| GitError::Spawn {
+                command: command.clone(),
+                source,
+            })?,
+            Err(e) => {
+                terminate(&mut child).await;
+                return Err(e);
+            }
+        };
+
+        Ok(GitOutput {
+            command,
+            status,
+            stdout,
+            stderr,
+        })
+    }
+
+    // The subcommand, for error messages: the first argument that is not a `-c` option.
+    fn label(&self) -> String {
+        let mut args = self.command.as_std().get_args();
+        while let Some(arg) = args.next() {
+            if arg == "-c" {
+                args.next();
+                continue;
+            }
+            return arg.to_string_lossy().to_string();
+        }
+        String::new()
+    }
+}
+
+async fn read_to_end(stream: Option<impl AsyncRead + Unpin>) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    if let Some(mut stream) = stream {
+        let _ = stream.read_to_end(&mut buffer).await;
+    }
+    buffer
+}
+
+async fn read_stderr(
+    stream: Option<impl AsyncRead + Unpin>,
+    on_line: Option<&mut (dyn FnMut(&str) + Send)>,
+) -> String {
+    let Some(on_line) = on_line else {
+        return String::from_utf8_lossy(&read_to_end(stream).await).to_string();
+    };
+    let Some(mut stream) = stream else {
+        return String::new();
+    };
+
+    let mut pending = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        for &byte in &buf[..n] {
+            if byte == b'\r' || byte == b'\n' {
+                let line = String::from_utf8_lossy(&pending).trim().to_string();
+                pending.clear();
+                if !line.is_empty() {
+                    on_line(&line);
+                }
+            } else {
+                pending.push(byte);
+            }
+        }
+    }
+
+    let line = String::from_utf8_lossy(&pending).trim().to_string();
+    if !line.is_empty() {
+        on_line(&line);
+    }
+    String::new()
+}
+
+// git removes its lock files when it gets SIGTERM; a SIGKILL would leave `index.lock` or
+// a ref lock behind and break every later command in the repository.
+async fn terminate(child: &mut Child) {
+    if request_termination(child)
+        && tokio::time::timeout(TERMINATE_GRACE_PERIOD, child.wait())
+            .await
+            .is_ok()
+    {
+        return;
+    }
+    let _ = child.kill().await;
+}
+
+#[cfg(unix)]
+fn request_termination(child: &Child) -> bool {
+    let Some(pid) = child.id() else {
+        return false;
+    };
+    // SAFETY: `pid` is our own child, which has not been reaped while `child` is alive.
+    unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) == 0 }
+}
+
+#[cfg(not(unix))]
+fn request_termination(_child: &Child) -> bool {
+    false
+}
+
+// Permits are taken repository first, then worktree, so the two levels cannot deadlock.
+async fn acquire_slots(path: &Path, lock: LockScope) -> Vec<OwnedSemaphorePermit> {
+    let (worktree_key, repository_key) = git_dirs(path);
+    let (repository_permits, worktree_permits) = match lock {
+        LockScope::Shared => (0, 1),
+        LockScope::Worktree => (1, MAX_GIT_PROCESSES_PER_REPOSITORY),
+        LockScope::Repository => (MAX_GIT_PROCESSES_PER_REPOSITORY, 0),
+    };
+
+    let mut permits = Vec::new();
+    for (key, count) in [
+        (repository_key, repository_permits),
+        (worktree_key, worktree_permits),
+    ] {
+        if count == 0 {
+            continue;
+        }
+        let semaphore = SLOTS
+            .lock()
+            .await
+            .entry(key)
+            .or_insert_with(|| Arc::new(Semaphore::new(MAX_GIT_PROCESSES_PER_REPOSITORY as usize)))
+            .clone();
+        // The semaphores are never closed, so acquiring only fails in theory.
+        if let Ok(permit) = semaphore.acquire_many_owned(count).await {
+            permits.push(permit);
+        }
+    }
+    permits
+}
+
+// The worktree's own git directory and the repository's common one. A linked worktree's
+// `.git` file points at `<common>/worktrees/<name>`, which names the common directory in
+// its `commondir` file.
+fn git_dirs(path: &Path) -> (PathBuf, PathBuf) {
+    let canonical = |p: &Path| fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+
+    for dir in path.ancestors() {
+        let dot_git = dir.join(".git");
+        if dot_git.is_dir() {
+            let git_dir = canonical(&dot_git);
+            return (git_dir.clone(), git_dir);
+        }
+        let Ok(content) = fs::read_to_string(&dot_git) else {
+            continue;
+        };
+        let Some(git_dir) = content.trim().strip_prefix("gitdir: ") else {
+            continue;
+        };
+
+        let git_dir = dir.join(git_dir);
+        let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+            Ok(common_dir) => canonical(&git_dir.join(common_dir.trim())),
+            Err(_) => canonical(&git_dir),
+        };
+        return (canonical(&git_dir), common_dir);
+    }
+
+    let path = canonical(path);
+    (path.clone(), path)
+}
+
+pub struct GitCancellation;
+
+impl GitCancellation {
+    // Runs `future` under a cancellation token registered as `operation_id`; every
+    // `GitCommand` started inside it stops when `cancel` is called with that id. An id
+    // that is already running is rejected.
+    pub async fn scope<F, T, E>(operation_id: Option<String>, future: F) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: From<GitError>,
+    {
+        let Some(operation_id) = operation_id else {
+            return future.await;
+        };
+
+        let token = CancellationToken::new();
+        let generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
+        {
+            let mut operations = OPERATIONS.lock().unwrap_or_else(PoisonError::into_inner);
+            if operations.contains_key(&operation_id) {
+                return Err(GitError::Invalid(format!(
+                    "Operation {} is already running",
+                    operation_id
+                ))
+                .into());
+            }
+            operations.insert(operation_id.clone(), (generation, token.clone()));
+        }
+
+        // Unregisters the operation even when the future is dropped half-way.
+        let _registration = Registration {
+            operation_id,
+            generation,
+        };
+        CANCELLATION.scope(token, future).await
+    }
+
+    // Returns false when no operation with that id is running.
+    pub fn cancel(operation_id: &str) -> bool {
+        let removed = OPERATIONS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(operation_id);
+        match removed {
+            Some((_, token)) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+struct Registration {
+    operation_id: String,
+    generation: u64,
+}
+
+impl Drop for Registration {
+    // `cancel` already unregisters the operation, after which the id may have been reused;
+    // only this scope's own entry is removed.
+    fn drop(&mut self) {
+        let mut operations = OPERATIONS.lock().unwrap_or_else(PoisonError::into_inner);
+        if operations
+            .get(&self.operation_id)
+            .is_some_and(|(generation, _)| *generation == self.generation)
+        {
+            operations.remove(&self.operation_id);
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use crate::models::{FileChangeStatus, FileChangeSummary, GitOperation, WorktreeGitStatus};
+use git2::{
+    Delta, DiffFindOptions, ErrorCode, Oid, Patch, Repository, RepositoryState, Status,
+    StatusOptions,
+};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::git::WorktreeInfo;
+
+static IN_PROCESS_READS: AtomicBool = AtomicBool::new(true);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitReadBackend {
+    // Every read forks the git CLI.
+    Cli,
+    // Reads go through libgit2 and fall back to the CLI when it cannot answer.
+    InProcess,
+}
+
+// Answers the hot read paths of `GitService` (worktree listing, ref lookups, status and
+// staged diff stats) with libgit2 instead of a `git` process. Everything that writes to a
+// repository stays on the CLI.
+pub struct GitReader;
+
+impl GitReader {
+    pub fn backend() -> GitReadBackend {
+        if IN_PROCESS_READS.load(Ordering::Relaxed) {
+            GitReadBackend::InProcess
+        } else {
+            GitReadBackend::Cli
+        }
+    }
+
+    pub fn set_backend(backend: GitReadBackend) {
+        IN_PROCESS_READS.store(backend == GitReadBackend::InProcess, Ordering::Relaxed);
+    }
+
+    // The main worktree comes first, as in `git worktree list`.
+    pub fn worktrees(path: &Path) -> Result<Vec<WorktreeInfo>, git2::Error> {
+        let repo = Repository::discover(path)?;
+        let main = if repo.is_worktree() {
+            Repository::open(repo.commondir())?
+        } else {
+            repo
+        };
+
+        let mut worktrees = vec![worktree_info(&main)?];
+        for name in main.worktrees()?.iter().flatten() {
+            let worktree = main.find_worktree(name)?;
+            let repo = Repository::open_from_worktree(&worktree)?;
+            worktrees.push(worktree_info(&repo)?);
+        }
+
+        Ok(worktrees)
+    }
+
+    pub fn reference_exists(path: &Path, name: &str) -> Result<bool, git2::Error> {
+        let repo = Repository::discover(path)?;
+        reference_exists(&repo, name)
+    }
+
+    pub fn current_branch(path: &Path) -> Result<Option<String>, git2::Error> {
+        let repo = Repository::discover(path)?;
+        head_branch(&repo)
+    }
+
+    // `None` when neither `origin/HEAD` nor a `main` or `master` branch exists.
+    pub fn default_branch(path: &Path) -> Result<Option<String>, git2::Error> {
+        let repo = Repository::discover(path)?;
+
+        match repo.find_reference("refs/remotes/origin/HEAD") {
+            Ok(reference) => {
+                if let Some(target) = reference.symbolic_target() {
+                    let branch = target
+                        .strip_prefix("refs/remotes/origin/")
+                        .unwrap_or(target);
+                    return Ok(Some(branch.to_string()));
+                }
+            }
+            Err(e) if e.code() == ErrorCode::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        for branch in ["main", "master"] {
+            if reference_exists(&repo, &format!("refs/heads/{}", branch))? {
+                return Ok(Some(branch.to_string()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    // `None` when the revision does not resolve.
+    pub fn rev_parse(path: &Path, reference: &str) -> Result<Option<String>, git2::Error> {
+        let repo = Repository::discover(path)?;
+        let object = repo.revparse_single(reference);
+        match object {
+            Ok(object) => Ok(Some(object.id().to_string())),
+            Err(e) if matches!(e.code(), ErrorCode::NotFound | ErrorCode::InvalidSpec) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn git_dir(path: &Path) -> Result<PathBuf, git2::Error> {
+        let repo = Repository::discover(path)?;
+        Ok(trim_trailing_separator(repo.path()))
+    }
+
+    // Mirrors `git status --porcelain=v2 --branch` plus the ahead/behind counts against
+    // `base_ref`.
+    pub fn status(path: &Path, base_ref: Option<&str>) -> Result<WorktreeGitStatus, git2::Error> {
+        let repo = Repository::discover(path)?;
+        let head = repo.refname_to_id("HEAD").ok();
+
+        let mut status = WorktreeGitStatus {
+            path: path.to_string_lossy().to_string(),
+            branch: head_branch(&repo)?,
+            head: head.map(|oid| oid.to_string()),
+            operation: operation(repo.state()),
+            ..Default::default()
+        };
+
+        // The upstream is reported even when its remote-tracking ref was never fetched.
+        if let Some(ref branch) = status.branch {
+            if let Ok(upstream) = repo.branch_upstream_name(&format!("refs/heads/{}", branch)) {
+                let upstream = upstream.as_str().unwrap_or_default();
+                status.upstream = Some(
+                    upstream
+                        .strip_prefix("refs/remotes/")
+                        .or_else(|| upstream.strip_prefix("refs/heads/"))
+                        .unwrap_or(upstream)
+                        .to_string(),
+                );
+                if let (Some(head), Ok(target)) = (head, repo.refname_to_id(upstream)) {
+                    let (ahead, behind) = repo.graph_ahead_behind(head, target)?;
+                    status.ahead_upstream = Some(ahead as u32);
+                    status.behind_upstream = Some(behind as u32);
+                }
+            }
+        }
+
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(false)
+            .include_ignored(false)
+            .renames_head_to_index(true);
+        for entry in repo.statuses(Some(&mut options))?.iter() {
+            let flags = entry.status();
+            if flags.is_conflicted() {
+                status
+                    .conflicted
+                    .push(String::from_utf8_lossy(entry.path_bytes()).to_string());
+            } else if flags.is_wt_new() {
+                status.untracked += 1;
+            } else {
+                if flags.intersects(
+                    Status::INDEX_NEW
+                        | Status::INDEX_MODIFIED
+                        | Status::INDEX_DELETED
+                        | Status::INDEX_RENAMED
+                        | Status::INDEX_TYPECHANGE,
+                ) {
+                    status.staged += 1;
+                }
+                if flags.intersects(
+                    Status::WT_MODIFIED
+                        | Status::WT_DELETED
+                        | Status::WT_RENAMED
+                        | Status::WT_TYPECHANGE,
+                ) {
+                    status.unstaged += 1;
+                }
+            }
+        }
+
+        // An unknown base ref only leaves the base counts empty.
+        if let Some(base_ref) = base_ref {
+            let base = repo
+                .revparse_single(base_ref)
+                .and_then(|object| object.peel_to_commit());
+            if let (Some(head), Ok(base)) = (head, base) {
+                let (ahead, behind) = repo.graph_ahead_behind(head, base.id())?;
+                status.ahead_base = Some(ahead as u32);
+                status.behind_base = Some(behind as u32);
+            }
+            status.base_ref = Some(base_ref.to_string());
+        }
+
+        Ok(status)
+    }
+
+    // The equivalent of `git diff -M --cached` summarised per file.
+    pub fn staged_changes(path: &Path) -> Result<Vec<FileChangeSummary>, git2::Error> {
+        let repo = Repository::discover(path)?;
+        // Before the first commit the index is compared against the empty tree.
+        let head = match repo.head() {
+            Ok(head) => Some(head.peel_to_tree()?),
+            Err(e) if e.code() == ErrorCode::UnbornBranch => None,
+            Err(e) => return Err(e),
+        };
+
+        let mut diff = repo.diff_tree_to_index(head.as_ref(), None, None)?;
+        diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+        let mut changes = Vec::new();
+        for index in 0..diff.deltas().len() {
+            let Some(patch) = Patch::from_diff(&diff, index)? else {
+                continue;
+            };
+            let delta = patch.delta();
+            let status = match delta.status() {
+                Delta::Added => FileChangeStatus::Added,
+                Delta::Modified => FileChangeStatus::Modified,
+                Delta::Deleted => FileChangeStatus::Deleted,
+                Delta::Renamed => FileChangeStatus::Renamed,
+                Delta::Copied => FileChangeStatus::Copied,
+                Delta::Typechange => FileChangeStatus::TypeChanged,
+                _ => FileChangeStatus::Unknown,
+            };
+            let old_path = match status {
+                FileChangeStatus::Renamed | FileChangeStatus::Copied => {
+                    delta.old_file().path().map(path_string)
+                }
+                _ => None,
+            };
+            let (additions, deletions) = if delta.flags().is_binary() {
+                (None, None)
+            } else {
+                let (_, additions, deletions) = patch.line_stats()?;
+                (Some(additions as u32), Some(deletions as u32))
+            };
+
+            changes.push(FileChangeSummary {
+                path: delta.new_file().path().map(path_string).unwrap_or_default(),
+                old_path,
+                status,
+                additions,
+                deletions,
+            });
+        }
+
+        Ok(changes)
+    }
+}
+
+fn worktree_info(repo: &Repository) -> Result<WorktreeInfo, git2::Error> {
+    // `git worktree list` prints neither HEAD nor a branch for a bare repository.
+    if repo.is_bare() {
+        return Ok(WorktreeInfo {
+            path: trim_trailing_separator(repo.path())
+                .to_string_lossy()
+                .to_string(),
+            branch: None,
+            head: String::new(),
+            is_bare: true,
+        });
+    }
+
+    let path = repo
+        .workdir()
+        .ok_or_else(|| git2::Error::from_str("Worktree has no working directory"))?;
+
+    Ok(WorktreeInfo {
+        path: trim_trailing_separator(path).to_string_lossy().to_string(),
+        branch: head_branch(repo)?,
+        // An unborn branch is listed with the null id.
+        head: repo
+            .refname_to_id("HEAD")
+            .unwrap_or_else(|_| Oid::zero())
+            .to_string(),
+        is_bare: false,
+    })
+}
+
+fn head_branch(repo: &Repository) -> Result<Option<String>, git2::Error> {
+    let head = repo.find_reference("HEAD")?;
+    Ok(head
+        .symbolic_target()
+        .map(|target| target.strip_prefix("refs/heads/").unwrap_or(target))
+        .map(str::to_string))
+}
+
+fn reference_exists(repo: &Repository, name: &str) -> Result<bool, git2::Error> {
+    match repo.find_reference(name) {
+        Ok(_) => Ok(true),
+        Err(e) if matches!(e.code(), ErrorCode::NotFound | ErrorCode::InvalidSpec) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+fn operation(state: RepositoryState) -> Option<GitOperation> {
+    match state {
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge
+        | RepositoryState::ApplyMailbox
+        | RepositoryState::ApplyMailboxOrRebase => Some(GitOperation::Rebase),
+        RepositoryState::Merge => Some(GitOperation::Merge),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+            Some(GitOperation::CherryPick)
+        }
+        RepositoryState::Revert | RepositoryState::RevertSequence => Some(GitOperation::Revert),
+        RepositoryState::Clean | RepositoryState::Bisect => None,
+    }
+}
+
+// libgit2 reports directories with a trailing '/', which the CLI does not print.
+fn trim_trailing_separator(path: &Path) -> PathBuf {
+    path.components().collect()
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
@@ -0,0 +1,203 @@
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use tokio::sync::Mutex;
+
+use super::github_client::GitHubClient;
+use crate::models::GitHubAppConfig;
+
+const STORE_PATH: &str = "github_app.json";
+const CONFIG_KEY: &str = "config";
+// GitHub rejects app JWTs that live longer than ten minutes; backdate iat for clock drift.
+const JWT_BACKDATE_SECONDS: i64 = 60;
+const JWT_LIFETIME_SECONDS: i64 = 540;
+const TOKEN_REFRESH_MARGIN_SECONDS: i64 = 300;
+
+struct CachedInstallationToken {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+static INSTALLATION_TOKENS: Lazy<Mutex<HashMap<i64, CachedInstallationToken>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+static REPOSITORY_INSTALLATIONS: Lazy<Mutex<HashMap<String, i64>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub struct InstallationCredentials {
+    pub host: String,
+    pub installation_id: i64,
+    pub token: String,
+}
+
+#[derive(Serialize)]
+struct AppClaims {
+    iat: i64,
+    exp: i64,
+    iss: String,
+}
+
+pub struct GitHubAppAuth;
+
+impl GitHubAppAuth {
+    pub fn load_config(app: &AppHandle) -> Result<Option<GitHubAppConfig>, String> {
+        let store = app
+            .store(STORE_PATH)
+            .map_err(|e| format!("Failed to open store: {}", e))?;
+
+        match store.get(CONFIG_KEY) {
+            Some(value) => {
+                let config: GitHubAppConfig = serde_json::from_value(value.clone())
+                    .map_err(|e| format!("Failed to deserialize: {}", e))?;
+                Ok(Some(config))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub async fn save_config(app: &AppHandle, config: &GitHubAppConfig) -> Result<(), String> {
+        if config.app_id.trim().is_empty() {
+            return Err("GitHub App ID is required".to_string());
+        }
+        Self::create_jwt(config)?;
+
+        let store = app
+            .store(STORE_PATH)
+            .map_err(|e| format!("Failed to open store: {}", e))?;
+
+        store.set(
+            CONFIG_KEY,
+            serde_json::to_value(config).map_err(|e| format!("Failed to serialize: {}", e))?,
+        );
+
+        store
+            .save()
+            .map_err(|e| format!("Failed to save store: {}", e))?;
+
+        Self::clear_cache().await;
+
+        Ok(())
+    }
+
+    pub async fn clear_config(app: &AppHandle) -> Result<(), String> {
+        let store = app
+            .store(STORE_PATH)
+            .map_err(|e| format!("Failed to open store: {}", e))?;
+
+        store.delete(CONFIG_KEY);
+
+        store
+            .save()
+            .map_err(|e| format!("Failed to save store: {}", e))?;
+
+        Self::clear_cache().await;
+
+        Ok(())
+    }
+
+    pub fn create_jwt(config: &GitHubAppConfig) -> Result<String, String> {
+        let pem = fs::read(&config.private_key_path)
+            .map_err(|e| format!("Failed to read GitHub App private key: {}", e))?;
+        let key = EncodingKey::from_rsa_pem(&pem)
+            .map_err(|e| format!("Invalid GitHub App private key: {}", e))?;
+
+        let now = Utc::now().timestamp();
+        let claims = AppClaims {
+            iat: now - JWT_BACKDATE_SECONDS,
+            exp: now + JWT_LIFETIME_SECONDS,
+            iss: config.app_id.trim().to_string(),
+        };
+
+        jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &key)
+            .map_err(|e| format!("Failed to sign GitHub App JWT: {}", e))
+    }
+
+    pub async fn installation_token(
+        app: &AppHandle,
+        owner: &str,
+        repo: &str,
+    ) -> Result<InstallationCredentials, String> {
+        let config =
+            Self::load_config(app)?.ok_or_else(|| "GitHub App is not configured".to_string())?;
+
+        let installation_id = match config.installation_id {
+            Some(id) => id,
+            None => Self::resolve_installation(&config, owner, repo).await?,
+        };
+
+        let mut tokens = INSTALLATION_TOKENS.lock().await;
+
+        if let Some(cached) = tokens.get(&installation_id) {
+            let refresh_at = cached.expires_at - Duration::seconds(TOKEN_REFRESH_MARGIN_SECONDS);
+            if Utc::now() < refresh_at {
+                return Ok(InstallationCredentials {
+                    host: config.host,
+                    installation_id,
+                    token: cached.token.clone(),
+                });
+            }
+        }
+
+        let jwt = Self::create_jwt(&config)?;
+        let response = GitHubClient::for_host(&config.host, jwt)
+            .create_installation_token(installation_id)
+            .await?;
+
+        let expires_at = DateTime::parse_from_rfc3339(&response.expires_at)
+            .map_err(|e| format!("Invalid installation token expiry: {}", e))?
+            .with_timezone(&Utc);
+
+        tokens.insert(
+            installation_id,
+            CachedInstallationToken {
+                token: response.token.clone(),
+                expires_at,
+            },
+        );
+
+        Ok(InstallationCredentials {
+            host: config.host,
+            installation_id,
+            token: response.token,
+        })
+    }
+
+    pub async fn invalidate_token(installation_id: i64) {
+        INSTALLATION_TOKENS.lock().await.remove(&installation_id);
+    }
+
+    async fn resolve_installation(
+        config: &GitHubAppConfig,
+        owner: &str,
+        repo: &str,
+    ) -> Result<i64, String> {
+        let key = format!("{}/{}", owner.to_lowercase(), repo.to_lowercase());
+
+        if let Some(id) = REPOSITORY_INSTALLATIONS.lock().await.get(&key) {
+            return Ok(*id);
+        }
+
+        let jwt = Self::create_jwt(config)?;
+        let installation = GitHubClient::for_host(&config.host, jwt)
+            .get_repository_installation(owner, repo)
+            .await
+            .map_err(|e| format!("GitHub App is not installed on {}: {}", key, e))?;
+
+        REPOSITORY_INSTALLATIONS
+            .lock()
+            .await
+            .insert(key, installation.id);
+
+        Ok(installation.id)
+    }
+
+    async fn clear_cache() {
+        INSTALLATION_TOKENS.lock().await.clear();
+        REPOSITORY_INSTALLATIONS.lock().await.clear();
+    }
+}
@@ -1,5 +1,6 @@
 use crate::models::{
-    CreatePullRequestRequest, Issue, Label, ListIssuesParams, Milestone, PullRequest,
+    CreateIssueCommentRequest, CreatePullRequestRequest, Issue, IssueComment, Label,
+    ListIssuesParams, Milestone, PullRequest,
 };
 use reqwest::Client;
 use serde::de::DeserializeOwned;
@@ -7,6 +8,12 @@ use serde::{Deserialize, Serialize};
 
 const GITHUB_API_BASE: &str = "https://api.github.com";
 
+const UNAUTHORIZED_ERROR_PREFIX: &str = "GitHub API error (401 Unauthorized)";
+
+pub fn is_unauthorized_error(error: &str) -> bool {
+    error.starts_with(UNAUTHORIZED_ERROR_PREFIX)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubUser {
     pub id: i64,
@@ -16,9 +23,45 @@ pub struct GitHubUser {
     pub avatar_url: String,
 }
 
+pub fn api_base_for_host(host: &str) -> String {
+    if host.eq_ignore_ascii_case("github.com") {
+        GITHUB_API_BASE.to_string()
+    } else {
+        format!("https://{}/api/v3", host)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubRepositoryOwner {
+    pub login: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubRepository {
+    pub id: i64,
+    pub name: String,
+    pub full_name: String,
+    pub owner: GitHubRepositoryOwner,
+    pub private: bool,
+    pub default_branch: String,
+    pub clone_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppInstallation {
+    pub id: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallationAccessToken {
+    pub token: String,
+    pub expires_at: String,
+}
+
 pub struct GitHubClient {
     client: Client,
     token: Option<String>,
+    api_base: String,
 }
 
 impl GitHubClient {
@@ -26,6 +69,7 @@ impl GitHubClient {
         Self {
             client: Client::new(),
             token: None,
+            api_base: GITHUB_API_BASE.to_string(),
         }
     }
 
@@ -33,6 +77,15 @@ impl GitHubClient {
         Self {
             client: Client::new(),
             token: Some(token),
+            api_base: GITHUB_API_BASE.to_string(),
+        }
+    }
+
+    pub fn for_host(host: &str, token: String) -> Self {
+        Self {
+            client: Client::new(),
+            token: Some(token),
+            api_base: api_base_for_host(host),
         }
     }
 
@@ -110,7 +163,7 @@ impl GitHubClient {
     }
 
     pub async fn get_authenticated_user(&self) -> Result<GitHubUser, String> {
-        let url = format!("{}/user", GITHUB_API_BASE);
+        let url = format!("{}/user", self.api_base);
         self.get_request(&url).await
     }
 
@@ -120,7 +173,7 @@ impl GitHubClient {
         repo: &str,
         params: &ListIssuesParams,
     ) -> Result<Vec<Issue>, String> {
-        let mut url = format!("{}/repos/{}/{}/issues", GITHUB_API_BASE, owner, repo);
+        let mut url = format!("{}/repos/{}/{}/issues", self.api_base, owner, repo);
 
         let mut query_params = vec![];
         if let Some(ref state) = params.state {
@@ -163,18 +216,31 @@ impl GitHubClient {
     ) -> Result<Issue, String> {
         let url = format!(
             "{}/repos/{}/{}/issues/{}",
-            GITHUB_API_BASE, owner, repo, issue_number
+            self.api_base, owner, repo, issue_number
         );
         self.get_request(&url).await
     }
 
     pub async fn list_labels(&self, owner: &str, repo: &str) -> Result<Vec<Label>, String> {
-        let url = format!("{}/repos/{}/{}/labels", GITHUB_API_BASE, owner, repo);
+        let url = format!("{}/repos/{}/{}/labels", self.api_base, owner, repo);
         self.get_request(&url).await
     }
 
     pub async fn list_milestones(&self, owner: &str, repo: &str) -> Result<Vec<Milestone>, String> {
-        let url = format!("{}/repos/{}/{}/milestones", GITHUB_API_BASE, owner, repo);
+        let url = format!("{}/repos/{}/{}/milestones", self.api_base, owner, repo);
+        self.get_request(&url).await
+    }
+
+    pub async fn get_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: i32,
+    ) -> Result<PullRequest, String> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}",
+            self.api_base, owner, repo, number
+        );
         self.get_request(&url).await
     }
 
@@ -184,9 +250,52 @@ impl GitHubClient {
         repo: &str,
         request: &CreatePullRequestRequest,
     ) -> Result<PullRequest, String> {
-        let url = format!("{}/repos/{}/{}/pulls", GITHUB_API_BASE, owner, repo);
+        let url = format!("{}/repos/{}/{}/pulls", self.api_base, owner, repo);
+        self.post_request(&url, request).await
+    }
+
+    pub async fn create_issue_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        issue_number: i32,
+        request: &CreateIssueCommentRequest,
+    ) -> Result<IssueComment, String> {
+        let url = format!(
+            "{}/repos/{}/{}/issues/{}/comments",
+            self.api_base, owner, repo, issue_number
+        );
         self.post_request(&url, request).await
     }
+
+    pub async fn get_repository(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<GitHubRepository, String> {
+        let url = format!("{}/repos/{}/{}", self.api_base, owner, repo);
+        self.get_request(&url).await
+    }
+
+    pub async fn get_repository_installation(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<AppInstallation, String> {
+        let url = format!("{}/repos/{}/{}/installation", self.api_base, owner, repo);
+        self.get_request(&url).await
+    }
+
+    pub async fn create_installation_token(
+        &self,
+        installation_id: i64,
+    ) -> Result<InstallationAccessToken, String> {
+        let url = format!(
+            "{}/app/installations/{}/access_tokens",
+            self.api_base, installation_id
+        );
+        self.post_request(&url, &serde_json::json!({})).await
+    }
 }
 
 impl Default for GitHubClient {
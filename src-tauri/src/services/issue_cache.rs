@@ -0,0 +1,66 @@
+use crate::models::Issue;
+use chrono::Utc;
+use rusqlite::params;
+use tauri::AppHandle;
+
+use super::database::{to_sql_text, Database};
+
+pub struct IssueCache;
+
+impl IssueCache {
+    pub fn store(app: &AppHandle, repository: &str, issues: &[Issue]) -> Result<(), String> {
+        let database = Database::from_app(app)?;
+        let fetched_at = Utc::now().to_rfc3339();
+
+        let rows = issues
+            .iter()
+            .map(|issue| {
+                let data = serde_json::to_string(issue)
+                    .map_err(|e| format!("Failed to serialize: {}", e))?;
+                Ok((issue, to_sql_text(&issue.state)?, data))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        database.with_connection(|conn| {
+            let tx = conn.transaction()?;
+            for (issue, state, data) in &rows {
+                tx.execute(
+                    "INSERT INTO issue_cache (repository, number, state, data, updated_at, fetched_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                     ON CONFLICT(repository, number) DO UPDATE SET
+                        state = excluded.state,
+                        data = excluded.data,
+                        updated_at = excluded.updated_at,
+                        fetched_at = excluded.fetched_at",
+                    params![
+                        repository,
+                        issue.number,
+                        state,
+                        data,
+                        issue.updated_at,
+                        fetched_at
+                    ],
+                )?;
+            }
+            tx.commit()
+        })
+    }
+
+    pub fn load(app: &AppHandle, repository: &str) -> Result<Vec<Issue>, String> {
+        let database = Database::from_app(app)?;
+
+        let rows: Vec<String> = database.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT data FROM issue_cache WHERE repository = ?1 ORDER BY number DESC",
+            )?;
+            let rows = stmt.query_map([repository], |row| row.get(0))?;
+            rows.collect()
+        })?;
+
+        rows.iter()
+            .map(|data| {
+                serde_json::from_str(data).map_err(|e| format!("Failed to deserialize: {}", e))
+            })
+            .collect()
+    }
+}
@@ -1,17 +1,59 @@
 pub mod agent_manager;
+pub mod board_store;
+pub mod commit_message;
+pub mod database;
+pub mod diff_parser;
+pub mod file_content;
 pub mod git;
+pub mod git_auth;
+pub mod git_command;
+pub mod git_reader;
+pub mod github_app;
 pub mod github_client;
+pub mod issue_cache;
 pub mod oauth;
 pub mod process;
 pub mod pty_manager;
+pub mod repository_config;
+pub mod repository_discovery;
 pub mod repository_store;
+pub mod session_store;
+pub mod settings_store;
+pub mod setup_approval_store;
+pub mod token_refresh;
 pub mod token_store;
+pub mod workspace;
+pub mod worktree_bootstrap;
+pub mod worktree_naming;
+pub mod worktree_store;
+pub mod worktree_watcher;
 
 pub use agent_manager::*;
+pub use board_store::*;
+pub use commit_message::*;
+pub use database::*;
+pub use diff_parser::*;
+pub use file_content::*;
 pub use git::*;
+pub use git_auth::*;
+pub use git_command::*;
+pub use git_reader::*;
+pub use github_app::*;
 pub use github_client::*;
+pub use issue_cache::*;
 pub use oauth::*;
 pub use process::*;
 pub use pty_manager::*;
+pub use repository_config::*;
+pub use repository_discovery::*;
 pub use repository_store::*;
+pub use session_store::*;
+pub use settings_store::*;
+pub use setup_approval_store::*;
+pub use token_refresh::*;
 pub use token_store::*;
+pub use workspace::*;
+pub use worktree_bootstrap::*;
+pub use worktree_naming::*;
+pub use worktree_store::*;
+pub use worktree_watcher::*;
@@ -6,9 +6,22 @@ const GITHUB_CLIENT_SECRET: Option<&str> = option_env!("GITHUB_CLIENT_SECRET");
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenResponse {
+    #[serde(default)]
     pub access_token: String,
+    #[serde(default)]
     pub token_type: String,
+    #[serde(default)]
     pub scope: String,
+    pub expires_in: Option<i64>,
+    pub refresh_token: Option<String>,
+    pub refresh_token_expires_in: Option<i64>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+pub enum RefreshOutcome {
+    Refreshed(TokenResponse),
+    Rejected(String),
 }
 
 pub struct OAuthConfig {
@@ -90,4 +103,48 @@ impl OAuthConfig {
             Err(format!("GitHub OAuth error ({}): {}", status, body))
         }
     }
+
+    pub async fn refresh_token(&self, refresh_token: &str) -> Result<RefreshOutcome, String> {
+        let client = Client::new();
+
+        let params = [
+            ("client_id", self.client_id.as_str()),
+            ("client_secret", self.client_secret.as_str()),
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ];
+
+        let response = client
+            .post("https://github.com/login/oauth/access_token")
+            .header("Accept", "application/json")
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("GitHub OAuth error ({}): {}", status, body));
+        }
+
+        let token_response: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        // GitHub reports a dead refresh token with a 200 and an error code in the body.
+        if let Some(error) = token_response.error {
+            let description = token_response.error_description.unwrap_or(error);
+            return Ok(RefreshOutcome::Rejected(description));
+        }
+
+        if token_response.access_token.is_empty() {
+            return Ok(RefreshOutcome::Rejected(
+                "GitHub returned an empty access token".to_string(),
+            ));
+        }
+
+        Ok(RefreshOutcome::Refreshed(token_response))
+    }
 }
@@ -0,0 +1,124 @@
+use crate::models::{DiscoveredRepository, Repository};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::git::{parse_remote_url, GitService};
+use super::token_store::DEFAULT_GITHUB_HOST;
+
+pub const DEFAULT_DISCOVERY_DEPTH: usize = 3;
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target"];
+const PREFERRED_REMOTES: &[&str] = &["origin", "upstream"];
+
+pub struct RepositoryDiscovery;
+
+impl RepositoryDiscovery {
+    // `hosts` lists the GitHub hosts with a signed-in account; github.com is always accepted.
+    pub async fn scan(
+        root: &Path,
+        max_depth: usize,
+        hosts: &HashSet<String>,
+        registered: &[Repository],
+    ) -> Result<Vec<DiscoveredRepository>, String> {
+        if !root.is_dir() {
+            return Err(format!("Directory does not exist: {:?}", root));
+        }
+
+        let mut repo_paths = Vec::new();
+        collect_repositories(root, max_depth, &mut repo_paths);
+        repo_paths.sort();
+
+        let mut discovered = Vec::new();
+        for path in repo_paths {
+            if let Some(repository) = inspect_repository(&path, hosts, registered).await {
+                discovered.push(repository);
+            }
+        }
+
+        Ok(discovered)
+    }
+}
+
+fn collect_repositories(dir: &Path, depth_left: usize, found: &mut Vec<PathBuf>) {
+    // A `.git` file marks a linked worktree or submodule, which is not registered on its own.
+    let git_dir = dir.join(".git");
+    if git_dir.is_dir() {
+        found.push(dir.to_path_buf());
+        return;
+    }
+    if git_dir.exists() || depth_left == 0 {
+        return;
+    }
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Skipping unreadable directory {:?}: {}", dir, e);
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        if !is_dir {
+            continue;
+        }
+
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.')
+            || SKIPPED_DIRS.contains(&name.as_ref())
+            || name.ends_with("-worktrees")
+        {
+            continue;
+        }
+
+        collect_repositories(&entry.path(), depth_left - 1, found);
+    }
+}
+
+async fn inspect_repository(
+    path: &Path,
+    hosts: &HashSet<String>,
+    registered: &[Repository],
+) -> Option<DiscoveredRepository> {
+    let remotes = match GitService::remotes(path).await {
+        Ok(remotes) => remotes,
+        Err(e) => {
+            eprintln!("Failed to read remotes of {:?}: {}", path, e);
+            return None;
+        }
+    };
+
+    let mut candidates: Vec<(String, (String, String, String))> = remotes
+        .into_iter()
+        .filter_map(|(remote, url)| parse_remote_url(&url).map(|parsed| (remote, parsed)))
+        .filter(|(_, (host, _, _))| host == DEFAULT_GITHUB_HOST || hosts.contains(host))
+        .collect();
+    candidates.sort_by_key(|(remote, _)| {
+        PREFERRED_REMOTES
+            .iter()
+            .position(|preferred| preferred == remote)
+            .unwrap_or(PREFERRED_REMOTES.len())
+    });
+
+    let (remote_name, (host, owner, name)) = candidates.into_iter().next()?;
+    let full_name = format!("{}/{}", owner, name);
+    let local_path = path.to_string_lossy().to_string();
+
+    let registered_id = registered
+        .iter()
+        .find(|r| Path::new(&r.local_path) == path || r.full_name.eq_ignore_ascii_case(&full_name))
+        .map(|r| r.id.clone());
+
+    Some(DiscoveredRepository {
+        local_path,
+        host,
+        owner,
+        name,
+        full_name,
+        remote_name,
+        default_branch: GitService::get_default_branch(path).await.ok(),
+        registered_id,
+    })
+}
@@ -1,72 +1,103 @@
 use crate::models::Repository;
+use rusqlite::{params, Row};
 use tauri::AppHandle;
-use tauri_plugin_store::StoreExt;
 
-const STORE_PATH: &str = "repositories.json";
-const REPOSITORIES_KEY: &str = "repositories";
+use super::database::Database;
+
+const REPOSITORY_COLUMNS: &str = "id, owner, name, full_name, local_path, default_branch, \
+     is_private, account_id, created_at, updated_at";
 
 pub struct RepositoryStore;
 
 impl RepositoryStore {
     pub fn save(app: &AppHandle, repository: Repository) -> Result<(), String> {
-        let store = app
-            .store(STORE_PATH)
-            .map_err(|e| format!("Failed to open store: {}", e))?;
-
-        let mut repositories: Vec<Repository> = Self::load_all(app)?
-            .into_iter()
-            .filter(|r| r.id != repository.id)
-            .collect();
-        repositories.push(repository);
-
-        store.set(
-            REPOSITORIES_KEY,
-            serde_json::to_value(&repositories)
-                .map_err(|e| format!("Failed to serialize: {}", e))?,
-        );
+        let database = Database::from_app(app)?;
 
-        store
-            .save()
-            .map_err(|e| format!("Failed to save store: {}", e))?;
-
-        Ok(())
+        database.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO repositories
+                 (id, owner, name, full_name, local_path, default_branch, is_private,
+                  account_id, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT(id) DO UPDATE SET
+                    owner = excluded.owner,
+                    name = excluded.name,
+                    full_name = excluded.full_name,
+                    local_path = excluded.local_path,
+                    default_branch = excluded.default_branch,
+                    is_private = excluded.is_private,
+                    account_id = excluded.account_id,
+                    updated_at = excluded.updated_at",
+                params![
+                    repository.id,
+                    repository.owner,
+                    repository.name,
+                    repository.full_name,
+                    repository.local_path,
+                    repository.default_branch,
+                    repository.is_private,
+                    repository.account_id,
+                    repository.created_at,
+                    repository.updated_at,
+                ],
+            )
+            .map(|_| ())
+        })
     }
 
     pub fn load_all(app: &AppHandle) -> Result<Vec<Repository>, String> {
-        let store = app
-            .store(STORE_PATH)
-            .map_err(|e| format!("Failed to open store: {}", e))?;
+        let database = Database::from_app(app)?;
 
-        match store.get(REPOSITORIES_KEY) {
-            Some(value) => {
-                let repositories: Vec<Repository> = serde_json::from_value(value.clone())
-                    .map_err(|e| format!("Failed to deserialize: {}", e))?;
-                Ok(repositories)
-            }
-            None => Ok(vec![]),
-        }
+        database.with_connection(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM repositories ORDER BY rowid",
+                REPOSITORY_COLUMNS
+            ))?;
+            let rows = stmt.query_map([], repository_from_row)?;
+            rows.collect()
+        })
     }
 
-    pub fn delete(app: &AppHandle, id: &str) -> Result<(), String> {
-        let store = app
-            .store(STORE_PATH)
-            .map_err(|e| format!("Failed to open store: {}", e))?;
-
-        let repositories: Vec<Repository> = Self::load_all(app)?
-            .into_iter()
-            .filter(|r| r.id != id)
-            .collect();
+    pub fn find_by_name(
+        app: &AppHandle,
+        owner: &str,
+        name: &str,
+    ) -> Result<Option<Repository>, String> {
+        let database = Database::from_app(app)?;
 
-        store.set(
-            REPOSITORIES_KEY,
-            serde_json::to_value(&repositories)
-                .map_err(|e| format!("Failed to serialize: {}", e))?,
-        );
+        database.with_connection(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM repositories
+                 WHERE owner = ?1 COLLATE NOCASE AND name = ?2 COLLATE NOCASE
+                 ORDER BY rowid LIMIT 1",
+                REPOSITORY_COLUMNS
+            ))?;
+            let mut rows = stmt.query_map(params![owner, name], repository_from_row)?;
+            rows.next().transpose()
+        })
+    }
 
-        store
-            .save()
-            .map_err(|e| format!("Failed to save store: {}", e))?;
+    pub fn delete(app: &AppHandle, id: &str) -> Result<(), String> {
+        let database = Database::from_app(app)?;
 
-        Ok(())
+        database.with_connection(|conn| {
+            conn.execute("DELETE FROM repositories WHERE id = ?1", [id])
+                .map(|_| ())
+        })
     }
 }
+
+fn repository_from_row(row: &Row<'_>) -> rusqlite::Result<Repository> {
+    Ok(Repository {
+        id: row.get(0)?,
+        owner: row.get(1)?,
+        name: row.get(2)?,
+        full_name: row.get(3)?,
+        local_path: row.get(4)?,
+        default_branch: row.get(5)?,
+        is_private: row.get(6)?,
+        account_id: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
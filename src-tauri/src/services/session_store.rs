@@ -0,0 +1,83 @@
+use crate::models::{AgentSession, AgentStatus};
+use chrono::Utc;
+use rusqlite::{params, Row};
+use tauri::AppHandle;
+
+use super::database::{from_sql_text, to_sql_text, Database};
+
+pub struct SessionStore;
+
+impl SessionStore {
+    pub fn record(app: &AppHandle, session: &AgentSession) -> Result<(), String> {
+        let database = Database::from_app(app)?;
+        let mode = to_sql_text(&session.mode)?;
+        let status = to_sql_text(&session.status)?;
+
+        database.with_connection(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO agent_sessions
+                 (id, worktree_path, mode, status, started_at, completed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    session.id,
+                    session.worktree_path,
+                    mode,
+                    status,
+                    session.started_at,
+                    session.completed_at,
+                ],
+            )
+            .map(|_| ())
+        })
+    }
+
+    pub fn update_status(
+        app: &AppHandle,
+        session_id: &str,
+        status: &AgentStatus,
+    ) -> Result<(), String> {
+        let database = Database::from_app(app)?;
+        let completed_at = match status {
+            AgentStatus::Completed | AgentStatus::Error => Some(Utc::now().to_rfc3339()),
+            _ => None,
+        };
+        let status = to_sql_text(status)?;
+
+        database.with_connection(|conn| {
+            conn.execute(
+                "UPDATE agent_sessions
+                 SET status = ?2, completed_at = COALESCE(completed_at, ?3)
+                 WHERE id = ?1",
+                params![session_id, status, completed_at],
+            )
+            .map(|_| ())
+        })
+    }
+
+    pub fn list_for_worktree(
+        app: &AppHandle,
+        worktree_path: &str,
+    ) -> Result<Vec<AgentSession>, String> {
+        let database = Database::from_app(app)?;
+
+        database.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, worktree_path, mode, status, started_at, completed_at
+                 FROM agent_sessions WHERE worktree_path = ?1 ORDER BY started_at DESC",
+            )?;
+            let rows = stmt.query_map([worktree_path], session_from_row)?;
+            rows.collect()
+        })
+    }
+}
+
+fn session_from_row(row: &Row<'_>) -> rusqlite::Result<AgentSession> {
+    Ok(AgentSession {
+        id: row.get(0)?,
+        worktree_path: row.get(1)?,
+        mode: from_sql_text(2, row.get(2)?)?,
+        status: from_sql_text(3, row.get(3)?)?,
+        started_at: row.get(4)?,
+        completed_at: row.get(5)?,
+    })
+}
@@ -0,0 +1,149 @@
+use crate::models::Settings;
+use chrono::Utc;
+use rusqlite::params;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+use super::database::Database;
+
+pub struct SettingsStore;
+
+impl SettingsStore {
+    pub fn load(app: &AppHandle) -> Result<Settings, String> {
+        let database = Database::from_app(app)?;
+
+        let rows: Vec<(String, String)> = database.with_connection(|conn| {
+            let mut stmt = conn.prepare("SELECT key, value FROM settings")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect()
+        })?;
+
+        let mut values = Map::new();
+        for (key, value) in rows {
+            match serde_json::from_str(&value) {
+                Ok(value) => {
+                    values.insert(key, value);
+                }
+                Err(e) => eprintln!("Ignoring unreadable setting {}: {}", key, e),
+            }
+        }
+
+        // Fall back to defaults field by field so one bad value does not reset everything.
+        let defaults = serde_json::to_value(Settings::default())
+            .map_err(|e| format!("Failed to serialize: {}", e))?;
+        let mut merged = defaults.as_object().cloned().unwrap_or_default();
+        for (key, value) in values {
+            if merged.contains_key(&key) {
+                let mut candidate = merged.clone();
+                candidate.insert(key.clone(), value.clone());
+                if serde_json::from_value::<Settings>(Value::Object(candidate)).is_ok() {
+                    merged.insert(key, value);
+                }
+            }
+        }
+
+        serde_json::from_value(Value::Object(merged))
+            .map_err(|e| format!("Failed to deserialize: {}", e))
+    }
+
+    pub fn update(app: &AppHandle, patch: Map<String, Value>) -> Result<Settings, String> {
+        let current = Self::load(app)?;
+
+        let mut merged = match serde_json::to_value(&current) {
+            Ok(Value::Object(map)) => map,
+            _ => return Err("Failed to serialize current settings".to_string()),
+        };
+        for (key, value) in patch {
+            if !merged.contains_key(&key) {
+                return Err(format!("Unknown setting: {}", key));
+            }
+            merged.insert(key, value);
+        }
+
+        let settings: Settings = serde_json::from_value(Value::Object(merged.clone()))
+            .map_err(|e| format!("Invalid settings: {}", e))?;
+        let settings = Self::validate(settings)?;
+
+        if settings == current {
+            return Ok(settings);
+        }
+
+        let values = match serde_json::to_value(&settings) {
+            Ok(Value::Object(map)) => map,
+            _ => return Err("Failed to serialize settings".to_string()),
+        };
+        let updated_at = Utc::now().to_rfc3339();
+
+        let database = Database::from_app(app)?;
+        database.with_connection(|conn| {
+            let tx = conn.transaction()?;
+            for (key, value) in &values {
+                tx.execute(
+                    "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, ?3)
+                     ON CONFLICT(key) DO UPDATE SET
+                        value = excluded.value,
+                        updated_at = excluded.updated_at",
+                    params![key, value.to_string(), updated_at],
+                )?;
+            }
+            tx.commit()
+        })?;
+
+        if let Err(e) = app.emit("settings-changed", &settings) {
+            eprintln!("Failed to emit settings-changed event: {}", e);
+        }
+
+        Ok(settings)
+    }
+
+    pub fn validate(mut settings: Settings) -> Result<Settings, String> {
+        settings.worktree_base_path = settings.worktree_base_path.trim().to_string();
+        settings.claude_cli_path = settings.claude_cli_path.trim().to_string();
+
+        if !settings.worktree_base_path.is_empty() {
+            let base = Path::new(&settings.worktree_base_path);
+            if !base.is_absolute() {
+                return Err(format!(
+                    "Worktree base path must be absolute: {}",
+                    settings.worktree_base_path
+                ));
+            }
+            if base.exists() && !base.is_dir() {
+                return Err(format!(
+                    "Worktree base path is not a directory: {}",
+                    settings.worktree_base_path
+                ));
+            }
+        }
+
+        if settings.claude_cli_path.is_empty() {
+            return Err("Claude CLI path must not be empty".to_string());
+        }
+        let cli = Path::new(&settings.claude_cli_path);
+        if cli.components().count() > 1 && !cli.is_file() {
+            return Err(format!(
+                "Claude CLI not found at {}",
+                settings.claude_cli_path
+            ));
+        }
+
+        Ok(settings)
+    }
+
+    pub fn worktrees_dir(settings: &Settings, repo_path: &Path) -> Result<PathBuf, String> {
+        let repo_name = repo_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| "Invalid repository path".to_string())?;
+
+        if settings.worktree_base_path.is_empty() {
+            Ok(repo_path
+                .parent()
+                .ok_or_else(|| "Cannot determine parent directory".to_string())?
+                .join(format!("{}-worktrees", repo_name)))
+        } else {
+            Ok(Path::new(&settings.worktree_base_path).join(repo_name))
+        }
+    }
+}
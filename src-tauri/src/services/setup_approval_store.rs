@@ -0,0 +1,59 @@
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use super::database::Database;
+
+// Setup commands come from files checked into the repository, so they only run once the
+// user approved that exact list for the repository. Changing the list asks again.
+pub struct SetupApprovalStore;
+
+impl SetupApprovalStore {
+    pub fn is_approved(
+        app: &AppHandle,
+        repository_path: &str,
+        commands: &[String],
+    ) -> Result<bool, String> {
+        let database = Database::from_app(app)?;
+        let hash = commands_hash(commands);
+
+        database.with_connection(|conn| {
+            conn.query_row(
+                "SELECT 1 FROM setup_approvals WHERE repository_path = ?1 AND commands_hash = ?2",
+                params![repository_path, hash],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|row| row.is_some())
+        })
+    }
+
+    pub fn approve(
+        app: &AppHandle,
+        repository_path: &str,
+        commands: &[String],
+    ) -> Result<(), String> {
+        let database = Database::from_app(app)?;
+        let hash = commands_hash(commands);
+        let now = Utc::now().to_rfc3339();
+
+        database.with_connection(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO setup_approvals (repository_path, commands_hash, approved_at)
+                 VALUES (?1, ?2, ?3)",
+                params![repository_path, hash, now],
+            )
+            .map(|_| ())
+        })
+    }
+}
+
+fn commands_hash(commands: &[String]) -> String {
+    let mut hasher = Sha256::new();
+    for command in commands {
+        hasher.update(command.as_bytes());
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
@@ -0,0 +1,131 @@
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+
+use super::github_client::GitHubClient;
+use super::oauth::{OAuthConfig, RefreshOutcome};
+use super::token_store::{StoredAccount, StoredAuth, TokenStore, DEFAULT_GITHUB_HOST};
+use crate::models::AuthExpiredEvent;
+
+// GitHub refresh tokens are single-use, so concurrent refreshes must be serialized
+// or the loser would present an already-consumed token and log the user out.
+static REFRESH_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+pub struct TokenRefresher;
+
+impl TokenRefresher {
+    fn load(app: &AppHandle, account_id: Option<&str>) -> Result<Option<StoredAccount>, String> {
+        match account_id {
+            Some(id) => TokenStore::load_account(app, id),
+            None => TokenStore::active_account(app),
+        }
+    }
+
+    pub async fn load_fresh(
+        app: &AppHandle,
+        account_id: Option<&str>,
+    ) -> Result<Option<StoredAccount>, String> {
+        let account = match Self::load(app, account_id)? {
+            Some(account) => account,
+            None if account_id.is_none() => match Self::migrate_legacy_account(app).await? {
+                Some(account) => account,
+                None => return Ok(None),
+            },
+            None => return Ok(None),
+        };
+
+        if !account.auth.needs_refresh(Utc::now()) {
+            return Ok(Some(account));
+        }
+
+        let _guard = REFRESH_LOCK.lock().await;
+
+        match TokenStore::load_account(app, &account.id())? {
+            Some(current) if !current.auth.needs_refresh(Utc::now()) => Ok(Some(current)),
+            Some(current) => Self::refresh(app, current).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    // Turns a legacy token stored without its user into an account by asking GitHub who
+    // it belongs to. The token stays stored when that fails, so it can be retried.
+    pub async fn migrate_legacy_account(app: &AppHandle) -> Result<Option<StoredAccount>, String> {
+        let Some(auth) = TokenStore::legacy_token(app)? else {
+            return Ok(None);
+        };
+
+        let client = GitHubClient::for_host(DEFAULT_GITHUB_HOST, auth.access_token.clone());
+        let user = client.get_authenticated_user().await?;
+        TokenStore::complete_legacy_migration(app, &user)?;
+
+        TokenStore::active_account(app)
+    }
+
+    pub async fn account(
+        app: &AppHandle,
+        account_id: Option<&str>,
+    ) -> Result<StoredAccount, String> {
+        Self::load_fresh(app, account_id)
+            .await?
+            .ok_or_else(|| match account_id {
+                Some(id) => format!("Account not found: {}", id),
+                None => "Not authenticated".to_string(),
+            })
+    }
+
+    pub async fn refresh_after_unauthorized(
+        app: &AppHandle,
+        account_id: &str,
+        rejected_token: &str,
+    ) -> Result<StoredAccount, String> {
+        let _guard = REFRESH_LOCK.lock().await;
+
+        let current = TokenStore::load_account(app, account_id)?
+            .ok_or_else(|| format!("Account not found: {}", account_id))?;
+
+        if current.auth.access_token != rejected_token {
+            return Ok(current);
+        }
+
+        if current.auth.refresh_token.is_none() {
+            return Err("GitHub rejected the stored access token".to_string());
+        }
+
+        Self::refresh(app, current).await
+    }
+
+    async fn refresh(app: &AppHandle, account: StoredAccount) -> Result<StoredAccount, String> {
+        let id = account.id();
+
+        let refresh_token = match account.auth.refresh_token.as_deref() {
+            Some(token) if !account.auth.is_refresh_token_expired(Utc::now()) => token,
+            _ => return Err(Self::expire(app, &id, "Refresh token has expired")),
+        };
+
+        let config = OAuthConfig::from_env()?;
+
+        match config.refresh_token(refresh_token).await? {
+            RefreshOutcome::Refreshed(response) => {
+                let auth = StoredAuth::from_token_response(response);
+                TokenStore::update_auth(app, &id, &auth)?;
+                Ok(StoredAccount { auth, ..account })
+            }
+            RefreshOutcome::Rejected(reason) => Err(Self::expire(app, &id, &reason)),
+        }
+    }
+
+    fn expire(app: &AppHandle, account_id: &str, reason: &str) -> String {
+        if let Err(e) = TokenStore::remove_account(app, account_id) {
+            eprintln!("Failed to remove expired account: {}", e);
+        }
+        let event = AuthExpiredEvent {
+            account_id: account_id.to_string(),
+            reason: reason.to_string(),
+        };
+        if let Err(e) = app.emit("auth-expired", event) {
+            eprintln!("Failed to emit auth-expired event: {}", e);
+        }
+        format!("GitHub authentication expired: {}", reason)
+    }
+}
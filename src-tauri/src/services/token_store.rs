@@ -3,15 +3,25 @@
 // For enhanced security, consider migrating to macOS Keychain (keychain-services crate)
 // or other platform-specific secure storage solutions.
 
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use tauri::AppHandle;
-use tauri_plugin_store::StoreExt;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Wry};
+use tauri_plugin_store::{Store, StoreExt};
+
+use crate::models::GitHubAccount;
 
 use super::github_client::GitHubUser;
+use super::oauth::TokenResponse;
 
 const STORE_PATH: &str = "auth.json";
 const TOKEN_KEY: &str = "github_access_token";
 const USER_KEY: &str = "github_user";
+const ACCOUNTS_KEY: &str = "accounts";
+const ACTIVE_ACCOUNT_KEY: &str = "active_account";
+pub const DEFAULT_GITHUB_HOST: &str = "github.com";
+const REFRESH_MARGIN_SECONDS: i64 = 300;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoredAuth {
@@ -19,50 +29,211 @@ pub struct StoredAuth {
     pub token_type: String,
     pub scope: String,
     pub stored_at: String,
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub refresh_token_expires_at: Option<String>,
+}
+
+impl StoredAuth {
+    pub fn from_token_response(response: TokenResponse) -> Self {
+        let now = Utc::now();
+        let expires_at = |seconds: i64| (now + Duration::seconds(seconds)).to_rfc3339();
+
+        Self {
+            access_token: response.access_token,
+            token_type: response.token_type,
+            scope: response.scope,
+            stored_at: now.to_rfc3339(),
+            expires_at: response.expires_in.map(expires_at),
+            refresh_token: response.refresh_token,
+            refresh_token_expires_at: response.refresh_token_expires_in.map(expires_at),
+        }
+    }
+
+    pub fn needs_refresh(&self, now: DateTime<Utc>) -> bool {
+        match parse_timestamp(self.expires_at.as_deref()) {
+            Some(expires_at) => now + Duration::seconds(REFRESH_MARGIN_SECONDS) >= expires_at,
+            None => false,
+        }
+    }
+
+    pub fn is_refresh_token_expired(&self, now: DateTime<Utc>) -> bool {
+        if self.refresh_token.is_none() {
+            return true;
+        }
+        match parse_timestamp(self.refresh_token_expires_at.as_deref()) {
+            Some(expires_at) => now >= expires_at,
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredAccount {
+    pub host: String,
+    pub auth: StoredAuth,
+    pub user: GitHubUser,
+}
+
+impl StoredAccount {
+    pub fn id(&self) -> String {
+        account_id(&self.host, &self.user.login)
+    }
+
+    pub fn to_summary(&self, active_id: Option<&str>) -> GitHubAccount {
+        let id = self.id();
+        GitHubAccount {
+            is_active: active_id == Some(id.as_str()),
+            id,
+            host: self.host.clone(),
+            login: self.user.login.clone(),
+            name: self.user.name.clone(),
+            avatar_url: self.user.avatar_url.clone(),
+        }
+    }
+}
+
+pub fn account_id(host: &str, login: &str) -> String {
+    format!("{}/{}", host.to_lowercase(), login.to_lowercase())
+}
+
+fn parse_timestamp(value: Option<&str>) -> Option<DateTime<Utc>> {
+    value
+        .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+        .map(|v| v.with_timezone(&Utc))
 }
 
 pub struct TokenStore;
 
 impl TokenStore {
-    pub fn save_token(app: &AppHandle, auth: &StoredAuth) -> Result<(), String> {
+    fn open(app: &AppHandle) -> Result<Arc<Store<Wry>>, String> {
         let store = app
             .store(STORE_PATH)
             .map_err(|e| format!("Failed to open store: {}", e))?;
 
-        store.set(
-            TOKEN_KEY,
-            serde_json::to_value(auth).map_err(|e| format!("Failed to serialize: {}", e))?,
-        );
+        Self::migrate_legacy_token(&store)?;
+
+        Ok(store)
+    }
+
+    // Older builds stored a single token/user pair at the top level of the store. A token
+    // without its user is kept until `TokenRefresher` has fetched the user for it.
+    fn migrate_legacy_token(store: &Store<Wry>) -> Result<(), String> {
+        let Some(token_value) = store.get(TOKEN_KEY) else {
+            return Ok(());
+        };
+
+        let auth: StoredAuth = serde_json::from_value(token_value)
+            .map_err(|e| format!("Failed to deserialize: {}", e))?;
+
+        if let Some(user_value) = store.get(USER_KEY) {
+            let user: GitHubUser = serde_json::from_value(user_value)
+                .map_err(|e| format!("Failed to deserialize: {}", e))?;
+            let account = StoredAccount {
+                host: DEFAULT_GITHUB_HOST.to_string(),
+                auth,
+                user,
+            };
+
+            let mut accounts = Self::read_accounts(store)?;
+            let id = account.id();
+            accounts.entry(id.clone()).or_insert(account);
+            Self::write_accounts(store, &accounts)?;
+
+            if store.get(ACTIVE_ACCOUNT_KEY).is_none() {
+                store.set(ACTIVE_ACCOUNT_KEY, serde_json::Value::String(id));
+            }
+
+            store.delete(TOKEN_KEY);
+        }
 
         store
             .save()
-            .map_err(|e| format!("Failed to save store: {}", e))?;
+            .map_err(|e| format!("Failed to save store: {}", e))
+    }
 
-        Ok(())
+    // A legacy token still waiting for its user, see `migrate_legacy_token`.
+    pub fn legacy_token(app: &AppHandle) -> Result<Option<StoredAuth>, String> {
+        let store = Self::open(app)?;
+        store
+            .get(TOKEN_KEY)
+            .map(|value| {
+                serde_json::from_value(value).map_err(|e| format!("Failed to deserialize: {}", e))
+            })
+            .transpose()
     }
 
-    pub fn load_token(app: &AppHandle) -> Result<Option<StoredAuth>, String> {
-        let store = app
-            .store(STORE_PATH)
-            .map_err(|e| format!("Failed to open store: {}", e))?;
+    pub fn complete_legacy_migration(app: &AppHandle, user: &GitHubUser) -> Result<(), String> {
+        let store = Self::open(app)?;
+        store.set(
+            USER_KEY,
+            serde_json::to_value(user).map_err(|e| format!("Failed to serialize: {}", e))?,
+        );
+        Self::migrate_legacy_token(&store)
+    }
 
-        match store.get(TOKEN_KEY) {
+    fn read_accounts(store: &Store<Wry>) -> Result<BTreeMap<String, StoredAccount>, String> {
+        match store.get(ACCOUNTS_KEY) {
             Some(value) => {
-                let auth: StoredAuth = serde_json::from_value(value.clone())
-                    .map_err(|e| format!("Failed to deserialize: {}", e))?;
-                Ok(Some(auth))
+                serde_json::from_value(value).map_err(|e| format!("Failed to deserialize: {}", e))
             }
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    fn write_accounts(
+        store: &Store<Wry>,
+        accounts: &BTreeMap<String, StoredAccount>,
+    ) -> Result<(), String> {
+        store.set(
+            ACCOUNTS_KEY,
+            serde_json::to_value(accounts).map_err(|e| format!("Failed to serialize: {}", e))?,
+        );
+        Ok(())
+    }
+
+    fn read_active_id(store: &Store<Wry>) -> Option<String> {
+        store
+            .get(ACTIVE_ACCOUNT_KEY)
+            .and_then(|value| value.as_str().map(|s| s.to_string()))
+    }
+
+    pub fn list_accounts(app: &AppHandle) -> Result<Vec<StoredAccount>, String> {
+        let store = Self::open(app)?;
+        Ok(Self::read_accounts(&store)?.into_values().collect())
+    }
+
+    pub fn list_account_summaries(app: &AppHandle) -> Result<Vec<GitHubAccount>, String> {
+        let store = Self::open(app)?;
+        let active_id = Self::read_active_id(&store);
+        Ok(Self::read_accounts(&store)?
+            .values()
+            .map(|account| account.to_summary(active_id.as_deref()))
+            .collect())
+    }
+
+    pub fn load_account(app: &AppHandle, id: &str) -> Result<Option<StoredAccount>, String> {
+        let store = Self::open(app)?;
+        Ok(Self::read_accounts(&store)?.remove(id))
+    }
+
+    pub fn active_account(app: &AppHandle) -> Result<Option<StoredAccount>, String> {
+        let store = Self::open(app)?;
+        match Self::read_active_id(&store) {
+            Some(id) => Ok(Self::read_accounts(&store)?.remove(&id)),
             None => Ok(None),
         }
     }
 
-    pub fn clear_token(app: &AppHandle) -> Result<(), String> {
-        let store = app
-            .store(STORE_PATH)
-            .map_err(|e| format!("Failed to open store: {}", e))?;
+    pub fn save_account(app: &AppHandle, account: &StoredAccount) -> Result<(), String> {
+        let store = Self::open(app)?;
 
-        store.delete(TOKEN_KEY);
-        store.delete(USER_KEY);
+        let mut accounts = Self::read_accounts(&store)?;
+        accounts.insert(account.id(), account.clone());
+        Self::write_accounts(&store, &accounts)?;
 
         store
             .save()
@@ -71,14 +242,39 @@ impl TokenStore {
         Ok(())
     }
 
-    pub fn save_user(app: &AppHandle, user: &GitHubUser) -> Result<(), String> {
-        let store = app
-            .store(STORE_PATH)
-            .map_err(|e| format!("Failed to open store: {}", e))?;
+    pub fn update_auth(app: &AppHandle, id: &str, auth: &StoredAuth) -> Result<(), String> {
+        let store = Self::open(app)?;
+
+        let mut accounts = Self::read_accounts(&store)?;
+        let account = accounts
+            .get_mut(id)
+            .ok_or_else(|| format!("Account not found: {}", id))?;
+        account.auth = auth.clone();
+        Self::write_accounts(&store, &accounts)?;
+
+        store
+            .save()
+            .map_err(|e| format!("Failed to save store: {}", e))?;
+
+        Ok(())
+    }
+
+    pub fn set_active_account(app: &AppHandle, id: &str) -> Result<(), String> {
+        let store = Self::open(app)?;
 
+        let accounts = Self::read_accounts(&store)?;
+        let account = accounts
+            .get(id)
+            .ok_or_else(|| format!("Account not found: {}", id))?;
+
+        store.set(
+            ACTIVE_ACCOUNT_KEY,
+            serde_json::Value::String(id.to_string()),
+        );
         store.set(
             USER_KEY,
-            serde_json::to_value(user).map_err(|e| format!("Failed to serialize: {}", e))?,
+            serde_json::to_value(&account.user)
+                .map_err(|e| format!("Failed to serialize: {}", e))?,
         );
 
         store
@@ -88,18 +284,49 @@ impl TokenStore {
         Ok(())
     }
 
-    pub fn load_user(app: &AppHandle) -> Result<Option<GitHubUser>, String> {
-        let store = app
-            .store(STORE_PATH)
-            .map_err(|e| format!("Failed to open store: {}", e))?;
+    pub fn remove_account(app: &AppHandle, id: &str) -> Result<(), String> {
+        let store = Self::open(app)?;
 
-        match store.get(USER_KEY) {
-            Some(value) => {
-                let user: GitHubUser = serde_json::from_value(value.clone())
-                    .map_err(|e| format!("Failed to deserialize: {}", e))?;
-                Ok(Some(user))
+        let mut accounts = Self::read_accounts(&store)?;
+        accounts.remove(id);
+        Self::write_accounts(&store, &accounts)?;
+
+        if Self::read_active_id(&store).as_deref() == Some(id) {
+            match accounts.values().next() {
+                Some(next) => {
+                    store.set(ACTIVE_ACCOUNT_KEY, serde_json::Value::String(next.id()));
+                    store.set(
+                        USER_KEY,
+                        serde_json::to_value(&next.user)
+                            .map_err(|e| format!("Failed to serialize: {}", e))?,
+                    );
+                }
+                None => {
+                    store.delete(ACTIVE_ACCOUNT_KEY);
+                    store.delete(USER_KEY);
+                }
             }
-            None => Ok(None),
         }
+
+        store
+            .save()
+            .map_err(|e| format!("Failed to save store: {}", e))?;
+
+        Ok(())
+    }
+
+    pub fn load_token(app: &AppHandle) -> Result<Option<StoredAuth>, String> {
+        Ok(Self::active_account(app)?.map(|account| account.auth))
+    }
+
+    pub fn clear_token(app: &AppHandle) -> Result<(), String> {
+        match Self::active_account(app)? {
+            Some(account) => Self::remove_account(app, &account.id()),
+            None => Ok(()),
+        }
+    }
+
+    pub fn load_user(app: &AppHandle) -> Result<Option<GitHubUser>, String> {
+        Ok(Self::active_account(app)?.map(|account| account.user))
     }
 }
@@ -0,0 +1,248 @@
+use crate::models::{
+    PathRemap, Repository, Settings, WorkspaceConflict, WorkspaceConflictKind, WorkspaceExport,
+    WorkspaceImportReport, WorkspaceRepository, HOME_PLACEHOLDER, WORKSPACE_FORMAT_VERSION,
+};
+use chrono::Utc;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use super::board_store::BoardStore;
+use super::repository_store::RepositoryStore;
+use super::settings_store::SettingsStore;
+use super::token_store::TokenStore;
+
+#[derive(Clone, Copy)]
+enum WorkspaceFormat {
+    Json,
+    Toml,
+}
+
+impl WorkspaceFormat {
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => WorkspaceFormat::Toml,
+            _ => WorkspaceFormat::Json,
+        }
+    }
+}
+
+pub struct WorkspaceService;
+
+impl WorkspaceService {
+    pub fn export(app: &AppHandle) -> Result<WorkspaceExport, String> {
+        let home = home_dir(app);
+
+        let repositories = RepositoryStore::load_all(app)?
+            .into_iter()
+            .map(|repository| WorkspaceRepository {
+                local_path: to_placeholder_path(&repository.local_path, home.as_deref()),
+                id: repository.id,
+                owner: repository.owner,
+                name: repository.name,
+                full_name: repository.full_name,
+                default_branch: repository.default_branch,
+                is_private: repository.is_private,
+                account_id: repository.account_id,
+            })
+            .collect();
+
+        let mut settings = SettingsStore::load(app)?;
+        if !settings.worktree_base_path.is_empty() {
+            settings.worktree_base_path =
+                to_placeholder_path(&settings.worktree_base_path, home.as_deref());
+        }
+
+        Ok(WorkspaceExport {
+            version: WORKSPACE_FORMAT_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            repositories,
+            settings,
+            board: BoardStore::load(app)?,
+        })
+    }
+
+    pub fn write_file(export: &WorkspaceExport, path: &Path) -> Result<(), String> {
+        let content = match WorkspaceFormat::from_path(path) {
+            WorkspaceFormat::Json => serde_json::to_string_pretty(export)
+                .map_err(|e| format!("Failed to serialize: {}", e))?,
+            WorkspaceFormat::Toml => {
+                toml::to_string_pretty(export).map_err(|e| format!("Failed to serialize: {}", e))?
+            }
+        };
+
+        fs::write(path, content).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+
+    pub fn read_file(path: &Path) -> Result<WorkspaceExport, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+
+        let export: WorkspaceExport = match WorkspaceFormat::from_path(path) {
+            WorkspaceFormat::Json => serde_json::from_str(&content)
+                .map_err(|e| format!("Invalid workspace file: {}", e))?,
+            WorkspaceFormat::Toml => {
+                toml::from_str(&content).map_err(|e| format!("Invalid workspace file: {}", e))?
+            }
+        };
+
+        if export.version > WORKSPACE_FORMAT_VERSION {
+            return Err(format!(
+                "Workspace file version {} is newer than supported version {}",
+                export.version, WORKSPACE_FORMAT_VERSION
+            ));
+        }
+
+        Ok(export)
+    }
+
+    pub fn import(
+        app: &AppHandle,
+        export: WorkspaceExport,
+        remaps: &[PathRemap],
+        overwrite: bool,
+    ) -> Result<WorkspaceImportReport, String> {
+        let home = home_dir(app);
+        let existing = RepositoryStore::load_all(app)?;
+        let mut report = WorkspaceImportReport::default();
+        let now = Utc::now().to_rfc3339();
+
+        for imported in export.repositories {
+            let local_path = resolve_path(&imported.local_path, home.as_deref(), remaps);
+            let current = existing.iter().find(|r| {
+                r.id == imported.id || r.full_name.eq_ignore_ascii_case(&imported.full_name)
+            });
+
+            if let Some(current) = current {
+                if !overwrite {
+                    report.conflicts.push(WorkspaceConflict {
+                        kind: WorkspaceConflictKind::RepositoryExists,
+                        subject: imported.full_name.clone(),
+                        message: format!("Already registered at {}", current.local_path),
+                    });
+                    report.skipped.push(imported.full_name);
+                    continue;
+                }
+            }
+
+            if !Path::new(&local_path).exists() {
+                report.conflicts.push(WorkspaceConflict {
+                    kind: WorkspaceConflictKind::MissingPath,
+                    subject: imported.full_name.clone(),
+                    message: format!("Local path does not exist: {}", local_path),
+                });
+            }
+
+            // An account of the exporting machine is unknown here; the repository then
+            // keeps the account it already had, or uses the active one.
+            let account_id = match imported.account_id {
+                Some(id) if TokenStore::load_account(app, &id)?.is_some() => Some(id),
+                Some(id) => {
+                    report.conflicts.push(WorkspaceConflict {
+                        kind: WorkspaceConflictKind::MissingAccount,
+                        subject: imported.full_name.clone(),
+                        message: format!("Account {} is not signed in", id),
+                    });
+                    current.and_then(|c| c.account_id.clone())
+                }
+                None => None,
+            };
+
+            let repository = Repository {
+                id: current
+                    .map(|c| c.id.clone())
+                    .unwrap_or_else(|| imported.id.clone()),
+                owner: imported.owner,
+                name: imported.name,
+                full_name: imported.full_name.clone(),
+                local_path,
+                default_branch: imported.default_branch,
+                is_private: imported.is_private,
+                account_id,
+                created_at: current
+                    .map(|c| c.created_at.clone())
+                    .unwrap_or_else(|| now.clone()),
+                updated_at: now.clone(),
+            };
+            RepositoryStore::save(app, repository)?;
+
+            if current.is_some() {
+                report.updated.push(imported.full_name);
+            } else {
+                report.added.push(imported.full_name);
+            }
+        }
+
+        let mut settings = export.settings;
+        if !settings.worktree_base_path.is_empty() {
+            settings.worktree_base_path =
+                resolve_path(&settings.worktree_base_path, home.as_deref(), remaps);
+        }
+        // Like repositories, settings that were already customised are only replaced with
+        // `overwrite`.
+        let current = SettingsStore::load(app)?;
+        if !overwrite && current != Settings::default() && current != settings {
+            report.conflicts.push(WorkspaceConflict {
+                kind: WorkspaceConflictKind::SettingsExist,
+                subject: "settings".to_string(),
+                message: "Settings are already configured".to_string(),
+            });
+        } else {
+            let patch = match serde_json::to_value(&settings) {
+                Ok(Value::Object(map)) => map,
+                _ => return Err("Failed to serialize imported settings".to_string()),
+            };
+            match SettingsStore::update(app, patch) {
+                Ok(_) => report.settings_applied = true,
+                Err(e) => report.conflicts.push(WorkspaceConflict {
+                    kind: WorkspaceConflictKind::InvalidSettings,
+                    subject: "settings".to_string(),
+                    message: e,
+                }),
+            }
+        }
+
+        if !export.board.columns.is_empty() || !export.board.saved_filters.is_empty() {
+            BoardStore::save(app, &export.board)?;
+            report.board_applied = true;
+        }
+
+        Ok(report)
+    }
+}
+
+fn home_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path().home_dir().ok()
+}
+
+fn to_placeholder_path(path: &str, home: Option<&Path>) -> String {
+    match home.and_then(|home| Path::new(path).strip_prefix(home).ok()) {
+        Some(relative) if relative.as_os_str().is_empty() => HOME_PLACEHOLDER.to_string(),
+        Some(relative) => format!("{}/{}", HOME_PLACEHOLDER, relative.to_string_lossy()),
+        None => path.to_string(),
+    }
+}
+
+fn resolve_path(path: &str, home: Option<&Path>, remaps: &[PathRemap]) -> String {
+    let expanded = match (path.strip_prefix(HOME_PLACEHOLDER), home) {
+        (Some(rest), Some(home)) => home.join(rest.trim_start_matches('/')),
+        _ => PathBuf::from(path),
+    };
+
+    // The longest matching prefix wins so nested remaps can override broader ones.
+    let remapped = remaps
+        .iter()
+        .filter(|remap| expanded.starts_with(&remap.from))
+        .max_by_key(|remap| remap.from.len())
+        .and_then(|remap| {
+            let rest = expanded.strip_prefix(&remap.from).ok()?;
+            if rest.as_os_str().is_empty() {
+                Some(PathBuf::from(&remap.to))
+            } else {
+                Some(Path::new(&remap.to).join(rest))
+            }
+        });
+
+    remapped.unwrap_or(expanded).to_string_lossy().to_string()
+}
@@ -0,0 +1,296 @@
+use crate::models::{BootstrapEventKind, RepositoryConfig, WorktreeBootstrapEvent};
+use serde::Deserialize;
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use tauri::{AppHandle, Emitter};
+
+use super::setup_approval_store::SetupApprovalStore;
+
+const CONDUCTOR_CONFIG_FILE: &str = "conductor.json";
+
+#[derive(Debug, Default, Deserialize)]
+struct ConductorConfig {
+    #[serde(default)]
+    scripts: ConductorScripts,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ConductorScripts {
+    setup: Option<String>,
+}
+
+pub enum BootstrapOutcome {
+    Completed,
+    // The setup commands have not been approved for this repository yet.
+    AwaitingApproval,
+}
+
+pub struct WorktreeBootstrap;
+
+impl WorktreeBootstrap {
+    // Copies/links the configured (typically gitignored) files from the main checkout,
+    // then runs the setup commands inside the worktree once they are approved. Progress
+    // is emitted as `worktree-bootstrap` events.
+    pub fn run(
+        app: &AppHandle,
+        repo_path: &Path,
+        worktree_path: &Path,
+        config: &RepositoryConfig,
+    ) -> Result<BootstrapOutcome, String> {
+        let emitter = BootstrapEmitter {
+            app,
+            worktree_path: worktree_path.to_string_lossy().to_string(),
+        };
+
+        let result = Self::run_stages(&emitter, repo_path, worktree_path, config);
+        match result {
+            Ok(BootstrapOutcome::Completed) => {
+                emitter.emit(BootstrapEventKind::Completed, None, String::new())
+            }
+            Ok(BootstrapOutcome::AwaitingApproval) => {}
+            Err(ref e) => emitter.emit(BootstrapEventKind::Failed, None, e.clone()),
+        }
+        result
+    }
+
+    fn run_stages(
+        emitter: &BootstrapEmitter,
+        repo_path: &Path,
+        worktree_path: &Path,
+        config: &RepositoryConfig,
+    ) -> Result<BootstrapOutcome, String> {
+        for file in &config.worktree.copy_files {
+            let source = repo_path.join(file);
+            if !source.exists() {
+                eprintln!("Skipping missing file from copy_files: {:?}", source);
+                continue;
+            }
+            copy_recursive(&source, &worktree_path.join(file))?;
+            emitter.emit(BootstrapEventKind::Copied, None, file.clone());
+        }
+
+        for file in &config.worktree.symlink_files {
+            let source = repo_path.join(file);
+            let destination = worktree_path.join(file);
+            if !source.exists() {
+                eprintln!("Skipping missing file from symlink_files: {:?}", source);
+                continue;
+            }
+            if destination.symlink_metadata().is_ok() {
+                continue;
+            }
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+            }
+            symlink(&source, &destination, source.is_dir())
+                .map_err(|e| format!("Failed to link {:?} into worktree: {}", source, e))?;
+            emitter.emit(BootstrapEventKind::Linked, None, file.clone());
+        }
+
+        let commands = Self::setup_commands(worktree_path, config)?;
+        let repository = repo_path.to_string_lossy();
+        if !commands.is_empty()
+            && !SetupApprovalStore::is_approved(emitter.app, &repository, &commands)?
+        {
+            emitter.emit(
+                BootstrapEventKind::ApprovalRequired,
+                None,
+                commands.join("\n"),
+            );
+            return Ok(BootstrapOutcome::AwaitingApproval);
+        }
+
+        for command in commands {
+            Self::run_command(emitter, repo_path, worktree_path, config, &command)?;
+        }
+
+        Ok(BootstrapOutcome::Completed)
+    }
+
+    // Explicit `worktree.setup` entries win; otherwise a checked-in conductor.json
+    // `scripts.setup` is honored.
+    pub fn setup_commands(
+        worktree_path: &Path,
+        config: &RepositoryConfig,
+    ) -> Result<Vec<String>, String> {
+        if !config.worktree.setup.is_empty() {
+            return Ok(config.worktree.setup.clone());
+        }
+
+        let path = worktree_path.join(CONDUCTOR_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+
+        let content =
+            fs::read_to_string(&path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        let conductor: ConductorConfig = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", CONDUCTOR_CONFIG_FILE, e))?;
+
+        Ok(conductor
+            .scripts
+            .setup
+            .filter(|command| !command.trim().is_empty())
+            .into_iter()
+            .collect())
+    }
+
+    fn run_command(
+        emitter: &BootstrapEmitter,
+        repo_path: &Path,
+        worktree_path: &Path,
+        config: &RepositoryConfig,
+        command: &str,
+    ) -> Result<(), String> {
+        emitter.emit(
+            BootstrapEventKind::CommandStarted,
+            Some(command),
+            String::new(),
+        );
+
+        // CONDUCTOR_ROOT_PATH keeps conductor.json scripts working unchanged.
+        let mut child = shell_command(command)
+            .current_dir(worktree_path)
+            .envs(&config.env)
+            .env("MARIONETTE_ROOT_PATH", repo_path)
+            .env("CONDUCTOR_ROOT_PATH", repo_path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start setup command {:?}: {}", command, e))?;
+
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+
+        thread::scope(|scope| {
+            if let Some(stdout) = stdout {
+                scope.spawn(|| stream_lines(emitter, command, BootstrapEventKind::Stdout, stdout));
+            }
+            if let Some(stderr) = stderr {
+                scope.spawn(|| stream_lines(emitter, command, BootstrapEventKind::Stderr, stderr));
+            }
+        });
+
+        let status = child.wait().map_err(|e| e.to_string())?;
+        emitter.emit(
+            BootstrapEventKind::CommandFinished,
+            Some(command),
+            status.to_string(),
+        );
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("Setup command {:?} failed: {}", command, status))
+        }
+    }
+}
+
+struct BootstrapEmitter<'a> {
+    app: &'a AppHandle,
+    worktree_path: String,
+}
+
+impl BootstrapEmitter<'_> {
+    fn emit(&self, kind: BootstrapEventKind, command: Option<&str>, message: String) {
+        let event = WorktreeBootstrapEvent {
+            worktree_path: self.worktree_path.clone(),
+            kind,
+            command: command.map(str::to_string),
+            message,
+        };
+        if let Err(e) = self.app.emit("worktree-bootstrap", event) {
+            eprintln!("Failed to emit worktree-bootstrap event: {}", e);
+        }
+    }
+}
+
+fn stream_lines(
+    emitter: &BootstrapEmitter,
+    command: &str,
+    kind: BootstrapEventKind,
+    stream: impl Read,
+) {
+    for line in BufReader::new(stream).lines() {
+        match line {
+            Ok(line) => emitter.emit(kind.clone(), Some(command), line),
+            Err(_) => break,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.args(["/C", command]);
+    cmd
+}
+
+// `is_dir` is only needed on Windows, where links to files and directories differ.
+#[cfg(unix)]
+fn symlink(target: &Path, destination: &Path, _is_dir: bool) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, destination)
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, destination: &Path, is_dir: bool) -> std::io::Result<()> {
+    if is_dir {
+        std::os::windows::fs::symlink_dir(target, destination)
+    } else {
+        std::os::windows::fs::symlink_file(target, destination)
+    }
+}
+
+// Symlinks are recreated as links to the same target rather than followed, so pnpm's
+// linked `node_modules` is not duplicated and link cycles cannot recurse forever.
+fn copy_recursive(source: &Path, destination: &Path) -> Result<(), String> {
+    let metadata =
+        fs::symlink_metadata(source).map_err(|e| format!("Failed to read {:?}: {}", source, e))?;
+
+    if metadata.file_type().is_symlink() {
+        let target =
+            fs::read_link(source).map_err(|e| format!("Failed to read {:?}: {}", source, e))?;
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+        }
+        if destination.symlink_metadata().is_ok() {
+            fs::remove_file(destination)
+                .map_err(|e| format!("Failed to replace {:?}: {}", destination, e))?;
+        }
+        return symlink(&target, destination, source.is_dir())
+            .map_err(|e| format!("Failed to link {:?} into worktree: {}", source, e));
+    }
+
+    if metadata.is_dir() {
+        fs::create_dir_all(destination)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", destination, e))?;
+        let entries =
+            fs::read_dir(source).map_err(|e| format!("Failed to read {:?}: {}", source, e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| e.to_string())?;
+            copy_recursive(&entry.path(), &destination.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+    fs::copy(source, destination)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to copy {:?} into worktree: {}", source, e))
+}
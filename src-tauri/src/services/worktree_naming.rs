@@ -0,0 +1,128 @@
+use deunicode::deunicode;
+use std::path::{Path, PathBuf};
+
+use super::git::GitService;
+
+pub const DEFAULT_BRANCH_TEMPLATE: &str = "issue-{number}-{slug}";
+pub const DEFAULT_DIRECTORY_TEMPLATE: &str = "issue-{number}-{slug}";
+const MAX_SLUG_LENGTH: usize = 40;
+const MAX_COLLISION_ATTEMPTS: u32 = 100;
+
+pub struct NamingContext<'a> {
+    pub number: i32,
+    pub title: &'a str,
+    pub user: Option<&'a str>,
+}
+
+pub struct WorktreeNames {
+    pub branch_name: String,
+    pub path: PathBuf,
+    // Set when the caller's custom branch already exists and is to be checked out.
+    pub branch_exists: bool,
+}
+
+pub struct WorktreeNaming;
+
+impl WorktreeNaming {
+    // Non-ASCII titles are transliterated first so they do not produce an empty slug. Kana
+    // become romaji but kanji become Mandarin pinyin, so "ログイン画面の修正" yields
+    // "roguinhua-mian-noxiu-zheng": stable ASCII, not readable Japanese. A custom branch
+    // name is the way out where that matters.
+    pub fn slugify(title: &str) -> String {
+        let transliterated = deunicode(title).to_lowercase();
+        let mut slug = String::new();
+
+        for c in transliterated.chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c);
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+
+        if slug.len() > MAX_SLUG_LENGTH {
+            slug.truncate(MAX_SLUG_LENGTH);
+        }
+        let slug = slug.trim_matches('-');
+
+        if slug.is_empty() {
+            "untitled".to_string()
+        } else {
+            slug.to_string()
+        }
+    }
+
+    pub fn render(template: &str, context: &NamingContext) -> Result<String, String> {
+        let mut rendered = template
+            .replace("{number}", &context.number.to_string())
+            .replace("{slug}", &Self::slugify(context.title));
+
+        if rendered.contains("{user}") {
+            let user = context.user.ok_or_else(|| {
+                "Template uses {user} but no GitHub account is signed in".to_string()
+            })?;
+            rendered = rendered.replace("{user}", &user.to_lowercase());
+        }
+
+        Ok(rendered)
+    }
+
+    // Picks a branch name and worktree directory that are both free. The branch comes from
+    // the template unless the caller asked for a custom name; both get a numeric suffix
+    // until neither the branch nor the directory exists. An existing custom branch is kept
+    // as is so it can be checked out, and only the directory is suffixed.
+    pub async fn resolve(
+        repo_path: &Path,
+        worktrees_dir: &Path,
+        custom_branch: Option<&str>,
+        branch_template: &str,
+        directory_template: &str,
+        context: &NamingContext<'_>,
+    ) -> Result<WorktreeNames, String> {
+        let base_branch = match custom_branch {
+            Some(name) => name.to_string(),
+            None => Self::render(branch_template, context)?,
+        };
+        GitService::check_branch_name(repo_path, &base_branch).await?;
+        let branch_exists =
+            custom_branch.is_some() && GitService::branch_exists(repo_path, &base_branch).await?;
+
+        let base_directory = Self::render(directory_template, context)?.replace(['/', '\\'], "-");
+
+        for attempt in 1..=MAX_COLLISION_ATTEMPTS {
+            let suffix = if attempt == 1 {
+                String::new()
+            } else {
+                format!("-{}", attempt)
+            };
+
+            let path = worktrees_dir.join(format!("{}{}", base_directory, suffix));
+            if path.exists() {
+                continue;
+            }
+
+            if branch_exists {
+                return Ok(WorktreeNames {
+                    branch_name: base_branch,
+                    path,
+                    branch_exists,
+                });
+            }
+
+            let candidate = format!("{}{}", base_branch, suffix);
+            if !GitService::branch_exists(repo_path, &candidate).await? {
+                GitService::check_branch_name(repo_path, &candidate).await?;
+                return Ok(WorktreeNames {
+                    branch_name: candidate,
+                    path,
+                    branch_exists,
+                });
+            }
+        }
+
+        Err(format!(
+            "Could not find a free worktree name for {:?} after {} attempts",
+            base_branch, MAX_COLLISION_ATTEMPTS
+        ))
+    }
+}
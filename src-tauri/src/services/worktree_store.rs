@@ -0,0 +1,271 @@
+use crate::models::{Worktree, WorktreeStatus, WorktreeStatusChange};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use super::database::{from_sql_text, to_sql_text, Database};
+use super::git::WorktreeInfo;
+
+const WORKTREE_COLUMNS: &str = "id, repository_id, issue_number, branch_name, path, status, \
+     base_ref, pull_request_number, pull_request_url, created_at, updated_at";
+
+pub struct WorktreeStore;
+
+impl WorktreeStore {
+    // Re-creating a worktree at a previously used path reuses its id, so the record is
+    // reset while the status history keeps growing.
+    pub fn save(app: &AppHandle, worktree: &Worktree) -> Result<(), String> {
+        let database = Database::from_app(app)?;
+        let status = to_sql_text(&worktree.status)?;
+
+        database.with_connection(|conn| {
+            let tx = conn.transaction()?;
+            let previous: Option<String> = tx
+                .query_row(
+                    "SELECT status FROM worktrees WHERE id = ?1",
+                    [&worktree.id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            tx.execute(
+                "INSERT INTO worktrees
+                 (id, repository_id, issue_number, branch_name, path, status, base_ref,
+                  pull_request_number, pull_request_url, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                 ON CONFLICT(id) DO UPDATE SET
+                    repository_id = excluded.repository_id,
+                    issue_number = excluded.issue_number,
+                    branch_name = excluded.branch_name,
+                    path = excluded.path,
+                    status = excluded.status,
+                    base_ref = excluded.base_ref,
+                    pull_request_number = excluded.pull_request_number,
+                    pull_request_url = excluded.pull_request_url,
+                    created_at = excluded.created_at,
+                    updated_at = excluded.updated_at",
+                params![
+                    worktree.id,
+                    worktree.repository_id,
+                    worktree.issue_number,
+                    worktree.branch_name,
+                    worktree.path,
+                    status,
+                    worktree.base_ref,
+                    worktree.pull_request_number,
+                    worktree.pull_request_url,
+                    worktree.created_at,
+                    worktree.updated_at,
+                ],
+            )?;
+            if previous.as_deref() != Some(status.as_str()) {
+                record_status(&tx, &worktree.id, &status, &worktree.updated_at)?;
+            }
+            tx.commit()
+        })
+    }
+
+    pub fn find_by_path(app: &AppHandle, path: &str) -> Result<Option<Worktree>, String> {
+        let database = Database::from_app(app)?;
+
+        database.with_connection(|conn| {
+            conn.query_row(
+                &format!("SELECT {} FROM worktrees WHERE path = ?1", WORKTREE_COLUMNS),
+                [path],
+                worktree_from_row,
+            )
+            .optional()
+        })
+    }
+
+    pub fn find_by_branch(
+        app: &AppHandle,
+        repository_id: &str,
+        branch_name: &str,
+    ) -> Result<Option<Worktree>, String> {
+        let removed = to_sql_text(&WorktreeStatus::Removed)?;
+        let database = Database::from_app(app)?;
+
+        database.with_connection(|conn| {
+            conn.query_row(
+                &format!(
+                    "SELECT {} FROM worktrees
+                     WHERE repository_id = ?1 AND branch_name = ?2 AND status != ?3
+                     ORDER BY created_at DESC LIMIT 1",
+                    WORKTREE_COLUMNS
+                ),
+                params![repository_id, branch_name, removed],
+                worktree_from_row,
+            )
+            .optional()
+        })
+    }
+
+    pub fn list_for_repository(
+        app: &AppHandle,
+        repository_id: &str,
+    ) -> Result<Vec<Worktree>, String> {
+        let removed = to_sql_text(&WorktreeStatus::Removed)?;
+        let database = Database::from_app(app)?;
+
+        database.with_connection(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM worktrees
+                 WHERE repository_id = ?1 AND status != ?2
+                 ORDER BY created_at",
+                WORKTREE_COLUMNS
+            ))?;
+            let rows = stmt.query_map(params![repository_id, removed], worktree_from_row)?;
+            rows.collect()
+        })
+    }
+
+    pub fn update_status(app: &AppHandle, id: &str, status: &WorktreeStatus) -> Result<(), String> {
+        let database = Database::from_app(app)?;
+        let status = to_sql_text(status)?;
+        let now = Utc::now().to_rfc3339();
+
+        database.with_connection(|conn| {
+            let tx = conn.transaction()?;
+            let changed = tx.execute(
+                "UPDATE worktrees SET status = ?2, updated_at = ?3 WHERE id = ?1 AND status != ?2",
+                params![id, status, now],
+            )?;
+            if changed > 0 {
+                record_status(&tx, id, &status, &now)?;
+            }
+            tx.commit()
+        })
+    }
+
+    pub fn link_pull_request(
+        app: &AppHandle,
+        id: &str,
+        number: i32,
+        url: &str,
+    ) -> Result<(), String> {
+        let database = Database::from_app(app)?;
+        let now = Utc::now().to_rfc3339();
+
+        database.with_connection(|conn| {
+            conn.execute(
+                "UPDATE worktrees
+                 SET pull_request_number = ?2, pull_request_url = ?3, updated_at = ?4
+                 WHERE id = ?1",
+                params![id, number, url, now],
+            )
+            .map(|_| ())
+        })
+    }
+
+    pub fn history(app: &AppHandle, id: &str) -> Result<Vec<WorktreeStatusChange>, String> {
+        let database = Database::from_app(app)?;
+
+        database.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT status, changed_at FROM worktree_status_history
+                 WHERE worktree_id = ?1 ORDER BY id",
+            )?;
+            let rows = stmt.query_map([id], |row| {
+                Ok(WorktreeStatusChange {
+                    status: from_sql_text(0, row.get(0)?)?,
+                    changed_at: row.get(1)?,
+                })
+            })?;
+            rows.collect()
+        })
+    }
+
+    // Brings the stored records in line with `git worktree list`: records whose checkout
+    // is gone are marked removed, and worktrees created outside the app under the managed
+    // directory are adopted.
+    pub fn reconcile(
+        app: &AppHandle,
+        repository_id: &str,
+        infos: &[WorktreeInfo],
+        managed_dir: Option<&Path>,
+        new_worktree: impl Fn(&WorktreeInfo, String) -> Worktree,
+    ) -> Result<Vec<Worktree>, String> {
+        let records = Self::list_for_repository(app, repository_id)?;
+        let infos: Vec<&WorktreeInfo> = infos.iter().filter(|info| !info.is_bare).collect();
+
+        for record in &records {
+            if !infos.iter().any(|info| same_path(&info.path, &record.path)) {
+                Self::update_status(app, &record.id, &WorktreeStatus::Removed)?;
+            }
+        }
+
+        let mut worktrees = Vec::new();
+        for info in infos {
+            match records.iter().find(|r| same_path(&info.path, &r.path)) {
+                Some(record) => {
+                    let mut worktree = record.clone();
+                    if let Some(ref branch) = info.branch {
+                        if *branch != worktree.branch_name {
+                            worktree.branch_name = branch.clone();
+                            worktree.updated_at = Utc::now().to_rfc3339();
+                            Self::save(app, &worktree)?;
+                        }
+                    }
+                    worktrees.push(worktree);
+                }
+                None if managed_dir.is_some_and(|dir| Path::new(&info.path).starts_with(dir)) => {
+                    let worktree = new_worktree(info, creation_time(Path::new(&info.path)));
+                    Self::save(app, &worktree)?;
+                    worktrees.push(worktree);
+                }
+                None => {}
+            }
+        }
+
+        Ok(worktrees)
+    }
+}
+
+fn record_status(
+    conn: &Connection,
+    worktree_id: &str,
+    status: &str,
+    changed_at: &str,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO worktree_status_history (worktree_id, status, changed_at)
+         VALUES (?1, ?2, ?3)",
+        params![worktree_id, status, changed_at],
+    )
+    .map(|_| ())
+}
+
+fn same_path(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    let canonical = |p: &str| fs::canonicalize(p).unwrap_or_else(|_| PathBuf::from(p));
+    canonical(a) == canonical(b)
+}
+
+fn creation_time(path: &Path) -> String {
+    let time = fs::metadata(path)
+        .and_then(|m| m.created().or_else(|_| m.modified()))
+        .map(DateTime::<Utc>::from)
+        .unwrap_or_else(|_| Utc::now());
+    time.to_rfc3339()
+}
+
+fn worktree_from_row(row: &Row<'_>) -> rusqlite::Result<Worktree> {
+    Ok(Worktree {
+        id: row.get(0)?,
+        repository_id: row.get(1)?,
+        issue_number: row.get(2)?,
+        branch_name: row.get(3)?,
+        path: row.get(4)?,
+        status: from_sql_text(5, row.get(5)?)?,
+        base_ref: row.get(6)?,
+        pull_request_number: row.get(7)?,
+        pull_request_url: row.get(8)?,
+        agent_session_ids: Vec::new(),
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
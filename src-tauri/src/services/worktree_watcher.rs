@@ -0,0 +1,364 @@
+use crate::models::WorktreeChangedEvent;
+use git2::Repository;
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher, WatcherKind};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+// A burst of writes is reported once it has been quiet for this long...
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(300);
+// ...or once it has been collecting for this long, so a continuous stream of writes
+// (e.g. a build) still produces events.
+const MAX_BATCH_DELAY: Duration = Duration::from_secs(2);
+
+enum WatcherMessage {
+    Event(notify::Result<Event>),
+    Stop,
+}
+
+struct WatchHandle {
+    owners: HashSet<String>,
+    message_tx: Sender<WatcherMessage>,
+}
+
+// Watches worktrees for file changes and emits debounced `worktree-changed` events.
+// A worktree is watched while at least one owner (the client, an agent session) holds it.
+pub struct WorktreeWatcher {
+    watches: HashMap<String, WatchHandle>,
+}
+
+impl WorktreeWatcher {
+    pub fn new() -> Self {
+        Self {
+            watches: HashMap::new(),
+        }
+    }
+
+    pub fn watch(
+        &mut self,
+        app_handle: AppHandle,
+        worktree_path: &str,
+        owner: &str,
+    ) -> Result<(), String> {
+        if let Some(handle) = self.watches.get_mut(worktree_path) {
+            handle.owners.insert(owner.to_string());
+            return Ok(());
+        }
+
+        let message_tx = spawn_watch(app_handle, worktree_path)?;
+        self.watches.insert(
+            worktree_path.to_string(),
+            WatchHandle {
+                owners: HashSet::from([owner.to_string()]),
+                message_tx,
+            },
+        );
+        Ok(())
+    }
+
+    // The watch stops once its last owner releases it.
+    pub fn unwatch(&mut self, worktree_path: &str, owner: &str) {
+        let Some(handle) = self.watches.get_mut(worktree_path) else {
+            return;
+        };
+        handle.owners.remove(owner);
+        if handle.owners.is_empty() {
+            self.stop(worktree_path);
+        }
+    }
+
+    // Stops watching regardless of owners, e.g. before the worktree is removed, and
+    // returns the owners so the watch can be restored if that fails.
+    pub fn stop(&mut self, worktree_path: &str) -> Vec<String> {
+        let Some(handle) = self.watches.remove(worktree_path) else {
+            return Vec::new();
+        };
+        let _ = handle.message_tx.send(WatcherMessage::Stop);
+        handle.owners.into_iter().collect()
+    }
+}
+
+impl Default for WorktreeWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+enum Change {
+    Ignored,
+    Git,
+    File(String),
+}
+
+struct WatchedWorktree {
+    repo: Repository,
+    root: PathBuf,
+    git_dirs: Vec<PathBuf>,
+    watcher: RecommendedWatcher,
+    // inotify needs a watch per directory and the number of watches is capped per user,
+    // so directories are watched one by one, skipping ignored ones like `target/` or
+    // `node_modules/`. Other backends watch the whole tree with a single watch.
+    per_directory: bool,
+    directories: HashSet<PathBuf>,
+}
+
+impl WatchedWorktree {
+    fn watch_all(&mut self) {
+        if self.per_directory {
+            let root = self.root.clone();
+            self.watch_tree(&root);
+        } else {
+            self.watch_path(&self.root.clone(), RecursiveMode::Recursive);
+        }
+
+        // A linked worktree keeps HEAD and its index under the main repository's
+        // `worktrees/<name>`, and shares the main repository's refs.
+        for dir in self.git_dirs.clone() {
+            if !self.per_directory && dir.starts_with(&self.root) {
+                continue;
+            }
+            self.watch_path(&dir, RecursiveMode::NonRecursive);
+            let refs = dir.join("refs");
+            if refs.is_dir() {
+                self.watch_path(&refs, RecursiveMode::Recursive);
+            }
+        }
+    }
+
+    fn watch_path(&mut self, path: &Path, mode: RecursiveMode) {
+        if let Err(e) = self.watcher.watch(path, mode) {
+            eprintln!("Failed to watch {:?}: {}", path, e);
+        }
+    }
+
+    // Returns the files found below `dir`, which may have been written before their
+    // directory was watched.
+    fn watch_tree(&mut self, dir: &Path) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            if self.directories.contains(&dir) {
+                continue;
+            }
+            if let Err(e) = self.watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                eprintln!("Failed to watch {:?}: {}", dir, e);
+                if matches!(e.kind, notify::ErrorKind::MaxFilesWatch) {
+                    break;
+                }
+                continue;
+            }
+            self.directories.insert(dir.clone());
+
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                // Symlinked directories are not followed.
+                if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                    if self.is_watched_directory(&path) {
+                        pending.push(path);
+                    }
+                } else {
+                    files.push(path);
+                }
+            }
+        }
+        files
+    }
+
+    fn is_watched_directory(&self, path: &Path) -> bool {
+        matches!(self.classify(path), Change::File(_))
+    }
+
+    fn handle(&mut self, event: Event, pending: &mut PendingChanges) {
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in &event.paths {
+            if self.per_directory {
+                self.track_directory(&event.kind, path, pending);
+            }
+            pending.record(self.classify(path));
+        }
+    }
+
+    fn track_directory(&mut self, kind: &EventKind, path: &Path, pending: &mut PendingChanges) {
+        if !matches!(
+            kind,
+            EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_)) | EventKind::Remove(_)
+        ) {
+            return;
+        }
+
+        let is_dir = fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_dir());
+        if is_dir {
+            if self.is_watched_directory(path) && !self.directories.contains(path) {
+                for file in self.watch_tree(path) {
+                    pending.record(self.classify(&file));
+                }
+            }
+        } else if self.directories.contains(path) {
+            // Covers directories moved away as well as deleted ones.
+            let removed: Vec<PathBuf> = self
+                .directories
+                .iter()
+                .filter(|dir| dir.starts_with(path))
+                .cloned()
+                .collect();
+            for dir in removed {
+                self.directories.remove(&dir);
+                let _ = self.watcher.unwatch(&dir);
+            }
+        }
+    }
+
+    fn classify(&self, path: &Path) -> Change {
+        if let Some(relative) = self
+            .git_dirs
+            .iter()
+            .find_map(|dir| path.strip_prefix(dir).ok())
+        {
+            // Lock files come and go around every write, new objects alone change nothing
+            // until a ref or the index points at them, and `worktrees/` holds the HEAD and
+            // index of the other worktrees.
+            let is_lock = relative.extension().is_some_and(|ext| ext == "lock");
+            let is_unrelated = relative.starts_with("objects") || relative.starts_with("worktrees");
+            return if is_lock || is_unrelated {
+                Change::Ignored
+            } else {
+                Change::Git
+            };
+        }
+
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return Change::Ignored;
+        };
+        if relative.as_os_str().is_empty() || relative.starts_with(".git") {
+            return Change::Ignored;
+        }
+        // Matched against the patterns only, so deleted paths are filtered too.
+        if self.repo.is_path_ignored(relative).unwrap_or(false) {
+            return Change::Ignored;
+        }
+
+        let relative = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(part) => Some(part.to_string_lossy()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        Change::File(relative)
+    }
+}
+
+#[derive(Default)]
+struct PendingChanges {
+    since: Option<Instant>,
+    paths: BTreeSet<String>,
+    git_changed: bool,
+}
+
+impl PendingChanges {
+    fn record(&mut self, change: Change) {
+        match change {
+            Change::Ignored => return,
+            Change::Git => self.git_changed = true,
+            Change::File(path) => {
+                self.paths.insert(path);
+            }
+        }
+        self.since.get_or_insert_with(Instant::now);
+    }
+}
+
+fn spawn_watch(
+    app_handle: AppHandle,
+    worktree_path: &str,
+) -> Result<Sender<WatcherMessage>, String> {
+    let repo = Repository::open(worktree_path)
+        .map_err(|e| format!("Failed to open repository {}: {}", worktree_path, e))?;
+    let canonical = |path: &Path| {
+        fs::canonicalize(path).map_err(|e| format!("Failed to resolve {:?}: {}", path, e))
+    };
+
+    // Events report canonical paths (e.g. /private/var on macOS).
+    let root = canonical(Path::new(worktree_path))?;
+    let mut git_dirs = vec![canonical(repo.path())?];
+    let common_dir = canonical(repo.commondir())?;
+    if !git_dirs.contains(&common_dir) {
+        git_dirs.push(common_dir);
+    }
+
+    let (message_tx, message_rx) = mpsc::channel();
+    let event_tx = message_tx.clone();
+    let watcher = notify::recommended_watcher(move |event| {
+        let _ = event_tx.send(WatcherMessage::Event(event));
+    })
+    .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+
+    let mut worktree = WatchedWorktree {
+        repo,
+        root,
+        git_dirs,
+        watcher,
+        per_directory: RecommendedWatcher::kind() == WatcherKind::Inotify,
+        directories: HashSet::new(),
+    };
+    let worktree_path = worktree_path.to_string();
+
+    // Walking a large worktree takes a while, so it happens off the caller's thread.
+    // Dropping the watcher when the loop ends stops the OS watches.
+    thread::spawn(move || {
+        worktree.watch_all();
+        let mut pending = PendingChanges::default();
+
+        loop {
+            let message = match pending.since {
+                None => message_rx
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected),
+                Some(since) => {
+                    let remaining = MAX_BATCH_DELAY.saturating_sub(since.elapsed());
+                    message_rx.recv_timeout(DEBOUNCE_DELAY.min(remaining))
+                }
+            };
+
+            let quiet = matches!(message, Err(RecvTimeoutError::Timeout));
+            match message {
+                Ok(WatcherMessage::Event(Ok(event))) => worktree.handle(event, &mut pending),
+                Ok(WatcherMessage::Event(Err(e))) => {
+                    eprintln!("File watcher error for {}: {}", worktree_path, e);
+                }
+                Ok(WatcherMessage::Stop) | Err(RecvTimeoutError::Disconnected) => break,
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+
+            let due = pending
+                .since
+                .is_some_and(|since| quiet || since.elapsed() >= MAX_BATCH_DELAY);
+            if due {
+                let changes = std::mem::take(&mut pending);
+                emit_changes(&app_handle, &worktree_path, changes);
+            }
+        }
+    });
+
+    Ok(message_tx)
+}
+
+fn emit_changes(app_handle: &AppHandle, worktree_path: &str, changes: PendingChanges) {
+    let event = WorktreeChangedEvent {
+        worktree_path: worktree_path.to_string(),
+        paths: changes.paths.into_iter().collect(),
+        git_changed: changes.git_changed,
+    };
+    let _ = app_handle.emit("worktree-changed", event);
+}